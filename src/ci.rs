@@ -0,0 +1,255 @@
+/// the environment variables CI providers set to the target branch of pull requests
+pub(crate) const BASE_BRANCH_VARIABLES: &[&str] = &[
+    // GitHub Actions
+    "GITHUB_BASE_REF",
+    // GitLab CI
+    "CI_MERGE_REQUEST_TARGET_BRANCH_NAME",
+    // Bitbucket Pipelines
+    "BITBUCKET_PR_DESTINATION_BRANCH",
+    // Azure Pipelines
+    "SYSTEM_PULLREQUEST_TARGETBRANCH",
+    // Buildkite
+    "BUILDKITE_PULL_REQUEST_BASE_BRANCH",
+    // Jenkins multibranch pipeline
+    "CHANGE_TARGET",
+    // Drone CI
+    "DRONE_TARGET_BRANCH",
+];
+
+/// finds the target branch of the pull request from the first non-empty variable
+/// in [`BASE_BRANCH_VARIABLES`]. returns the variable name and the branch name
+pub(crate) fn base_branch(env: impl Fn(&str) -> Option<String>) -> Option<(&'static str, String)> {
+    BASE_BRANCH_VARIABLES.iter().find_map(|&name| {
+        let value = env(name)?;
+        let branch = value.trim();
+        // azure pipelines gives full ref name
+        let branch = branch.strip_prefix("refs/heads/").unwrap_or(branch);
+        Some((name, branch.to_string())).filter(|(_, x)| !x.is_empty())
+    })
+}
+
+/// the commits to check found in the GitHub Actions event payload at `GITHUB_EVENT_PATH`
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) enum GithubEvent {
+    /// the hashes and messages of pushed commits, newest first like `git log`.
+    /// the newest is the pushed head. the payload has no parents so the oldest commit
+    /// should be verified to have `before` as the parent if any, or the payload is truncated
+    Commits {
+        commits: Vec<(String, String)>,
+        before: Option<String>,
+    },
+    /// the range to check with git if the payload has no commit messages
+    Range { head: String, base: String },
+}
+
+pub(crate) fn read_github_event(path: &str) -> Result<GithubEvent, String> {
+    let source = std::fs::read_to_string(path).map_err(|e| format!("reading event: {e}"))?;
+    parse_github_event(&source)
+}
+
+fn parse_github_event(source: &str) -> Result<GithubEvent, String> {
+    github_event(&parse_payload(source)?)
+}
+
+fn parse_payload(source: &str) -> Result<serde_json::Value, String> {
+    serde_json::from_str(source).map_err(|e| format!("malformed event payload: {e}"))
+}
+
+fn github_event(payload: &serde_json::Value) -> Result<GithubEvent, String> {
+    let str_at = |pointer: &str| payload.pointer(pointer).and_then(|x| x.as_str());
+
+    // pull_request event. synchronize events also have before and after of the head branch
+    if let (Some(head), Some(base)) = (
+        str_at("/pull_request/head/sha"),
+        str_at("/pull_request/base/sha"),
+    ) {
+        return Ok(GithubEvent::Range {
+            head: head.to_string(),
+            base: base.to_string(),
+        });
+    }
+    // push event
+    if let (Some(commits), Some(head), Some(base)) = (
+        payload["commits"].as_array().filter(|x| !x.is_empty()),
+        str_at("/after"),
+        str_at("/before"),
+    ) {
+        let commits = commits
+            .iter()
+            .map(|x| {
+                Some((
+                    x["id"].as_str()?.to_string(),
+                    x["message"].as_str()?.to_string(),
+                ))
+            })
+            .rev()
+            .collect::<Option<Vec<_>>>();
+        // GitHub truncates the commits array to the oldest ones for large pushes
+        if let Some(commits) = commits.filter(|x| x[0].0 == head) {
+            let before = Some(base.to_string()).filter(|x| !x.bytes().all(|x| x == b'0'));
+            return Ok(GithubEvent::Commits { commits, before });
+        }
+    }
+    // the commits array may be truncated or lack messages: use git instead
+    if let (Some(head), Some(base)) = (str_at("/after"), str_at("/before")) {
+        if base.bytes().all(|x| x == b'0') {
+            return Err("the push created a new branch: no base commit in event payload".into());
+        }
+        return Ok(GithubEvent::Range {
+            head: head.to_string(),
+            base: base.to_string(),
+        });
+    }
+    Err("no commits found in event payload".into())
+}
+
+/// reads the head and base commits of the pull_request event payload
+pub(crate) fn read_pull_request(path: &str) -> Result<(String, String), String> {
+    let source = std::fs::read_to_string(path).map_err(|e| format!("reading event: {e}"))?;
+    parse_pull_request(&source)
+}
+
+fn parse_pull_request(source: &str) -> Result<(String, String), String> {
+    let payload = parse_payload(source)?;
+    if payload.get("pull_request").is_none() {
+        return Err("not a pull_request event payload".into());
+    }
+    match github_event(&payload) {
+        Ok(GithubEvent::Range { head, base }) => Ok((head, base)),
+        _ => Err("not a pull_request event payload".into()),
+    }
+}
+
+#[test]
+fn parse_github_event_test() {
+    assert_eq!(
+        parse_github_event(
+            r#"{
+                "before": "1111111111111111111111111111111111111111",
+                "after": "3333333333333333333333333333333333333333",
+                "commits": [
+                    { "id": "2222222222222222222222222222222222222222", "message": "feat: a" },
+                    { "id": "3333333333333333333333333333333333333333", "message": "fix: b" }
+                ]
+            }"#
+        ),
+        Ok(GithubEvent::Commits {
+            commits: vec![
+                (
+                    "3333333333333333333333333333333333333333".into(),
+                    "fix: b".into()
+                ),
+                (
+                    "2222222222222222222222222222222222222222".into(),
+                    "feat: a".into()
+                ),
+            ],
+            before: Some("1111111111111111111111111111111111111111".into()),
+        })
+    );
+    // truncated: the newest is not the pushed head
+    assert_eq!(
+        parse_github_event(
+            r#"{
+                "before": "1111111111111111111111111111111111111111",
+                "after": "3333333333333333333333333333333333333333",
+                "commits": [
+                    { "id": "2222222222222222222222222222222222222222", "message": "feat: a" }
+                ]
+            }"#
+        ),
+        Ok(GithubEvent::Range {
+            head: "3333333333333333333333333333333333333333".into(),
+            base: "1111111111111111111111111111111111111111".into(),
+        })
+    );
+    // new branch: no parent to verify
+    assert_eq!(
+        parse_github_event(
+            r#"{
+                "before": "0000000000000000000000000000000000000000",
+                "after": "3333333333333333333333333333333333333333",
+                "commits": [
+                    { "id": "3333333333333333333333333333333333333333", "message": "fix: b" }
+                ]
+            }"#
+        ),
+        Ok(GithubEvent::Commits {
+            commits: vec![(
+                "3333333333333333333333333333333333333333".into(),
+                "fix: b".into()
+            )],
+            before: None,
+        })
+    );
+    assert_eq!(
+        parse_github_event(r#"{ "before": "b", "after": "a", "commits": [{ "id": "a" }] }"#),
+        Ok(GithubEvent::Range {
+            head: "a".into(),
+            base: "b".into(),
+        })
+    );
+    assert_eq!(
+        parse_github_event(
+            r#"{ "pull_request": { "head": { "sha": "h" }, "base": { "sha": "b" } } }"#
+        ),
+        Ok(GithubEvent::Range {
+            head: "h".into(),
+            base: "b".into(),
+        })
+    );
+    assert!(parse_github_event(r#"{ "before": "0000", "after": "a", "commits": [] }"#).is_err());
+    assert!(parse_github_event("{}").is_err());
+    assert!(parse_github_event("[").is_err());
+}
+
+#[test]
+fn parse_pull_request_test() {
+    assert_eq!(
+        parse_pull_request(
+            r#"{ "pull_request": { "head": { "sha": "h" }, "base": { "sha": "b" } } }"#
+        ),
+        Ok(("h".into(), "b".into()))
+    );
+    // synchronize event
+    assert_eq!(
+        parse_pull_request(
+            r#"{
+                "before": "x",
+                "after": "h",
+                "pull_request": { "head": { "sha": "h" }, "base": { "sha": "b" } }
+            }"#
+        ),
+        Ok(("h".into(), "b".into()))
+    );
+    assert!(parse_pull_request(r#"{ "before": "b", "after": "a" }"#).is_err());
+    assert!(parse_pull_request(r#"{ "pull_request": {} }"#).is_err());
+}
+
+#[test]
+fn base_branch_test() {
+    let env = |vars: &'static [(&'static str, &'static str)]| {
+        move |name: &str| {
+            vars.iter()
+                .find(|(x, _)| *x == name)
+                .map(|(_, x)| x.to_string())
+        }
+    };
+    assert_eq!(base_branch(env(&[])), None);
+    assert_eq!(
+        base_branch(env(&[("GITHUB_BASE_REF", "main")])),
+        Some(("GITHUB_BASE_REF", "main".to_string()))
+    );
+    // github sets empty GITHUB_BASE_REF for push events
+    assert_eq!(
+        base_branch(env(&[("GITHUB_BASE_REF", ""), ("CHANGE_TARGET", "dev")])),
+        Some(("CHANGE_TARGET", "dev".to_string()))
+    );
+    assert_eq!(
+        base_branch(env(&[(
+            "SYSTEM_PULLREQUEST_TARGETBRANCH",
+            "refs/heads/main"
+        )])),
+        Some(("SYSTEM_PULLREQUEST_TARGETBRANCH", "main".to_string()))
+    );
+}
@@ -0,0 +1,705 @@
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub(crate) const CONFIG_FILE_NAME: &str = ".conventional-commitlint.toml";
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub(crate) struct Config {
+    /// the set of rules to run
+    pub profile: Profile,
+    /// the allowed types and their descriptions.
+    /// accepts both of `["feat", "fix"]` and `{ feat = "A new feature" }` forms
+    #[serde(deserialize_with = "deserialize_types")]
+    #[schemars(with = "Types")]
+    pub types: BTreeMap<String, Option<String>>,
+    /// the file to load `types` from instead, relative to the config file.
+    /// JSON files contain the list or map; other files are TOML with `types` key like config.
+    /// cannot be used together with `types`
+    pub types_file: Option<PathBuf>,
+    /// the prefix of experimental types like `x-`. types with it are accepted with a warning
+    pub experimental_prefix: Option<String>,
+    /// words must not be in the header. matched case-insensitively and as a whole word
+    pub forbidden_words: Vec<String>,
+    /// check forbidden words in body, not only header
+    pub forbidden_words_in_body: bool,
+    /// in check mode, verify the subject of revert commits mirrors the subject of reverted commit
+    pub revert_subject_match: bool,
+    /// the words subject can end with without being a sentence like `etc.`.
+    /// matched case-insensitively as the suffix of the last word
+    pub sentence_end_allowlist: Vec<String>,
+    /// the maximum number of characters in the first line
+    pub header_max_length: Option<usize>,
+    /// the maximum number of characters in the subject after `type(scope): `
+    pub subject_max_length: Option<usize>,
+    /// the minimum number of characters in non-empty subject
+    pub subject_min_length: Option<usize>,
+    /// disallow mixed casing like `fIx` or `ADd` in the first word of subject
+    pub forbid_weird_subject_casing: bool,
+    /// the words allowed in any casing for `forbid-weird-subject-casing` like `gRPC`.
+    /// well-known names like `GitHub` and `macOS` are always allowed
+    pub acronyms: Vec<String>,
+    /// the maximum length of body lines. footers and lines of a single word like URL are not checked.
+    /// used as the width to wrap body in `edit --fix`
+    pub body_max_line_length: Option<usize>,
+    /// the minimum number of words in the first body paragraph before any list or footer
+    pub body_description_min_words: Option<usize>,
+    /// in check mode, warn if the same subject appears more than this count consecutively
+    pub max_repeated_subjects: Option<usize>,
+    /// in edit mode, strip comments before linting
+    pub strip_comments: bool,
+    /// in edit mode, also strip the `Conflicts:` block older git writes without comment char
+    /// to merge and squash messages. this may strip the block written by the user
+    pub strip_conflicts: bool,
+    /// the character comment lines start with. defaults to `core.commentChar` of git or `#`
+    pub comment_char: Option<char>,
+    /// the map from type to changelog section for `check --sections`
+    pub sections: BTreeMap<String, String>,
+    /// require `Reviewed-by`, `Acked-by`, and `Tested-by` footers to be `Name <email>` format
+    pub review_trailers: bool,
+    /// require `revert` type commits to reference the reverted commit in body
+    pub revert_requires_reference: bool,
+    /// disallow consecutive blank lines in body. `edit --fix` collapses them
+    pub forbid_multiple_blank_lines: bool,
+    /// warn if non-whitespace characters in subject and body are fewer than this
+    pub min_description_length: Option<usize>,
+    /// the types `min-description-length` applies to
+    pub min_description_types: Vec<String>,
+    /// the allowed scopes matched case-sensitively. any scope is allowed if not set
+    pub scopes: Option<Vec<String>>,
+    /// whether scope is required, forbidden, or optional for each type.
+    /// types not in the map are optional
+    pub scope_policy: BTreeMap<String, ScopePolicy>,
+    /// the types scope is required for. shorthand of `required` in `scope-policy`
+    pub scope_required_for: Vec<String>,
+    /// require the scope to be lowercase like the type, leaving the subject to other rules
+    pub prefix_lowercase: bool,
+    /// disallow subjects whose first word is the scope like `feat(login): login`
+    pub forbid_subject_restating_scope: bool,
+    /// disallow `http://` and `https://` URLs in subject
+    pub forbid_url_in_subject: bool,
+    /// in edit mode, require the message file to end with a newline. `edit --fix` adds it
+    pub require_trailing_newline: bool,
+    /// in check mode, warn `revert` type commits reverting `feat` commit without breaking mark
+    pub revert_breaking_hint: bool,
+    /// the maximum number of footers. `true` enables the check with the default limit
+    #[serde(deserialize_with = "deserialize_max_footers")]
+    #[schemars(with = "Option<MaxFooters>")]
+    pub max_footers: Option<usize>,
+    /// in check mode, disallow `feat` and `fix` commits without file changes.
+    /// commits with `Allow-Empty` footer are exempted
+    pub forbid_empty_changeset: bool,
+    /// disallow issue closing keywords like `Closes #1` outside of the footer block
+    pub forbid_closing_keywords_in_body: bool,
+    /// the issue closing keywords. matched case-insensitively
+    pub closing_keywords: Vec<String>,
+    /// in check mode, warn commits changing files in more top-level directories than this.
+    /// merge commits are exempted
+    pub max_top_level_dirs: Option<usize>,
+    /// require `perf` commits to quantify the improvement in subject or body
+    pub require_perf_claim: bool,
+    /// the regex of quantified claims for `require-perf-claim` like `30%` or `2x`
+    #[serde(deserialize_with = "deserialize_regex")]
+    #[schemars(with = "String")]
+    pub perf_claim_pattern: Regex,
+    /// the only issue reference style allowed in body. a key of `issue-reference-patterns`
+    pub issue_reference_style: Option<String>,
+    /// the regex of each issue reference style
+    #[serde(deserialize_with = "deserialize_patterns")]
+    #[schemars(with = "BTreeMap<String, String>")]
+    pub issue_reference_patterns: BTreeMap<String, Regex>,
+    /// with `check --monorepo`, the config file for the commits changing files in each directory.
+    /// both are relative to the repository root
+    pub package_configs: BTreeMap<String, PathBuf>,
+    pub messages: Messages,
+    /// the severity of errors keyed by error code like `HeaderSubjectEmpty`.
+    /// only errors make the command fail
+    pub severity: BTreeMap<String, Severity>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum Profile {
+    /// all rules enabled by config
+    Default,
+    /// only checks the header starts with known type, colon, and space for gradual adoption
+    Minimal,
+}
+
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum ScopePolicy {
+    Required,
+    Forbidden,
+    #[default]
+    Optional,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum Severity {
+    Error,
+    Warning,
+}
+
+/// overrides of the messages to show
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub(crate) struct Messages {
+    /// the banner shown before errors of each commit in check mode. `{hash}` is replaced
+    pub banner: Option<String>,
+    /// the banner shown before errors in edit mode
+    pub edit_banner: Option<String>,
+    /// the messages of errors keyed by error code like `HeaderSubjectEmpty`
+    pub errors: BTreeMap<String, String>,
+}
+
+impl Messages {
+    pub fn banner(&self, hash: impl Display) -> String {
+        match &self.banner {
+            Some(banner) => banner.replace("{hash}", &hash.to_string()),
+            None => format!("we found errors in commit message of {hash}"),
+        }
+    }
+
+    pub fn edit_banner(&self) -> &str {
+        self.edit_banner
+            .as_deref()
+            .unwrap_or("we found errors in commit message:")
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            profile: Profile::Default,
+            types: [
+                (
+                    "build",
+                    "Changes that affect the build system or external dependencies",
+                ),
+                ("chore", "Other changes that don't modify src or test files"),
+                ("ci", "Changes to CI configuration files and scripts"),
+                ("docs", "Documentation only changes"),
+                ("feat", "A new feature"),
+                ("fix", "A bug fix"),
+                ("perf", "A code change that improves performance"),
+                (
+                    "refactor",
+                    "A code change that neither fixes a bug nor adds a feature",
+                ),
+                ("revert", "Reverts a previous commit"),
+                (
+                    "style",
+                    "Changes that do not affect the meaning of the code",
+                ),
+                ("test", "Adding missing tests or correcting existing tests"),
+            ]
+            .into_iter()
+            .map(|(ty, description)| (ty.to_string(), Some(description.to_string())))
+            .collect(),
+            types_file: None,
+            experimental_prefix: None,
+            forbidden_words: Vec::new(),
+            forbidden_words_in_body: false,
+            revert_subject_match: false,
+            sentence_end_allowlist: vec!["etc.".to_string()],
+            header_max_length: None,
+            subject_max_length: None,
+            subject_min_length: None,
+            forbid_weird_subject_casing: false,
+            acronyms: vec![],
+            body_max_line_length: None,
+            body_description_min_words: None,
+            max_repeated_subjects: None,
+            strip_comments: true,
+            strip_conflicts: false,
+            comment_char: None,
+            sections: [
+                ("feat", "Features"),
+                ("fix", "Bug Fixes"),
+                ("perf", "Performance Improvements"),
+                ("revert", "Reverts"),
+            ]
+            .into_iter()
+            .map(|(ty, section)| (ty.to_string(), section.to_string()))
+            .collect(),
+            review_trailers: false,
+            revert_requires_reference: false,
+            forbid_multiple_blank_lines: false,
+            min_description_length: None,
+            min_description_types: ["feat", "fix", "perf", "refactor"]
+                .map(String::from)
+                .to_vec(),
+            scopes: None,
+            scope_policy: BTreeMap::new(),
+            scope_required_for: vec![],
+            prefix_lowercase: false,
+            forbid_subject_restating_scope: false,
+            forbid_url_in_subject: false,
+            require_trailing_newline: false,
+            revert_breaking_hint: false,
+            max_footers: None,
+            forbid_empty_changeset: false,
+            forbid_closing_keywords_in_body: false,
+            max_top_level_dirs: None,
+            require_perf_claim: false,
+            perf_claim_pattern: Regex::new(
+                r"(?i)\d+(\.\d+)?\s?(%|(x|times|ms|us|µs|ns|s|kb|mb|gb)\b)",
+            )
+            .unwrap(),
+            closing_keywords: ["Closes", "Fixes", "Resolves"].map(String::from).to_vec(),
+            issue_reference_style: None,
+            issue_reference_patterns: [
+                ("hash", r"\B#\d+\b"),
+                ("gh", r"\bGH-\d+\b"),
+                ("url", r"https?://\S+/issues/\d+\b"),
+            ]
+            .into_iter()
+            .map(|(style, pattern)| (style.to_string(), Regex::new(pattern).unwrap()))
+            .collect(),
+            package_configs: BTreeMap::new(),
+            messages: Messages::default(),
+            severity: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    /// loads config file in the root of the repository containing `dir`.
+    /// if there are no config file, returns default config
+    pub fn load(dir: &Path) -> Result<Self, ConfigError> {
+        let dir = std::env::current_dir().map_err(ConfigError::Io)?.join(dir);
+        match Self::load_file(&find_config_file(&dir)) {
+            Err(ConfigError::Io(e)) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            result => result,
+        }
+    }
+
+    /// loads the config file. unlike `load`, the file must exist
+    pub fn load_file(path: &Path) -> Result<Self, ConfigError> {
+        let source = std::fs::read_to_string(path).map_err(ConfigError::Io)?;
+        let mut config: Self = toml::from_str(&source).map_err(ConfigError::Toml)?;
+        if let Some(types_file) = &config.types_file {
+            let table: toml::Table = toml::from_str(&source).map_err(ConfigError::Toml)?;
+            if table.contains_key("types") {
+                return Err(ConfigError::Invalid(
+                    "types and types-file cannot be used together".to_owned(),
+                ));
+            }
+            let types_path = path.parent().unwrap_or(Path::new("")).join(types_file);
+            config.types =
+                load_types_file(&types_path).map_err(|e| ConfigError::TypesFile(types_path, e))?;
+        }
+        config.validate().map_err(ConfigError::Invalid)?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(style) = &self.issue_reference_style {
+            if !self.issue_reference_patterns.contains_key(style) {
+                return Err(format!(
+                    "issue-reference-style {style} is not in issue-reference-patterns"
+                ));
+            }
+        }
+        let forbidden = |ty: &&String| self.scope_policy.get(*ty) == Some(&ScopePolicy::Forbidden);
+        if let Some(ty) = self.scope_required_for.iter().find(forbidden) {
+            return Err(format!(
+                "scope-required-for contains {ty} but scope-policy of {ty} is forbidden"
+            ));
+        }
+        let unknown = |x: &&String| !crate::MessageError::CODES.contains(&x.as_str());
+        if let Some(code) = self.severity.keys().find(unknown) {
+            return Err(format!("unknown error code {code} in severity"));
+        }
+        if let Some(code) = self.messages.errors.keys().find(unknown) {
+            return Err(format!("unknown error code {code} in messages.errors"));
+        }
+        Ok(())
+    }
+}
+
+/// returns the path of the config file in the root of the repository containing `dir`.
+/// if `dir` is not in a repository, returns the path in `dir`
+fn find_config_file(dir: &Path) -> PathBuf {
+    let root = dir
+        .ancestors()
+        .find(|x| x.join(".git").exists())
+        .unwrap_or(dir);
+    root.join(CONFIG_FILE_NAME)
+}
+
+/// the JSON Schema of the config file for editor completion
+pub(crate) fn json_schema() -> String {
+    let schema = schemars::schema_for!(Config);
+    serde_json::to_string_pretty(&schema).unwrap()
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum Types {
+    List(Vec<String>),
+    Map(BTreeMap<String, String>),
+}
+
+impl Types {
+    fn into_map(self) -> BTreeMap<String, Option<String>> {
+        match self {
+            Types::List(list) => list.into_iter().map(|ty| (ty, None)).collect(),
+            Types::Map(map) => map.into_iter().map(|(ty, x)| (ty, Some(x))).collect(),
+        }
+    }
+}
+
+fn deserialize_types<'de, D>(deserializer: D) -> Result<BTreeMap<String, Option<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Types::deserialize(deserializer)?.into_map())
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TypesFile {
+    types: Types,
+}
+
+/// loads the types of `types-file`
+fn load_types_file(path: &Path) -> Result<BTreeMap<String, Option<String>>, String> {
+    let source = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let types = if path.extension().is_some_and(|x| x == "json") {
+        serde_json::from_str::<Types>(&source).map_err(|e| e.to_string())?
+    } else {
+        toml::from_str::<TypesFile>(&source)
+            .map_err(|e| e.to_string())?
+            .types
+    };
+    Ok(types.into_map())
+}
+
+/// the limit of `max-footers = true`
+pub(crate) const DEFAULT_MAX_FOOTERS: usize = 16;
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum MaxFooters {
+    Enabled(bool),
+    Max(usize),
+}
+
+fn deserialize_max_footers<'de, D>(deserializer: D) -> Result<Option<usize>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match MaxFooters::deserialize(deserializer)? {
+        MaxFooters::Enabled(true) => Some(DEFAULT_MAX_FOOTERS),
+        MaxFooters::Enabled(false) => None,
+        MaxFooters::Max(max) => Some(max),
+    })
+}
+
+fn deserialize_patterns<'de, D>(deserializer: D) -> Result<BTreeMap<String, Regex>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let patterns = BTreeMap::<String, String>::deserialize(deserializer)?;
+    patterns
+        .into_iter()
+        .map(|(name, pattern)| match Regex::new(&pattern) {
+            Ok(regex) => Ok((name, regex)),
+            Err(e) => Err(serde::de::Error::custom(format!("pattern {name}: {e}"))),
+        })
+        .collect()
+}
+
+fn deserialize_regex<'de, D>(deserializer: D) -> Result<Regex, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let pattern = String::deserialize(deserializer)?;
+    Regex::new(&pattern).map_err(serde::de::Error::custom)
+}
+
+#[derive(Debug)]
+pub(crate) enum ConfigError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    Invalid(String),
+    TypesFile(PathBuf, String),
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "reading config file: {e}"),
+            ConfigError::Toml(e) => write!(f, "parsing config file: {e}"),
+            ConfigError::Invalid(e) => write!(f, "invalid config file: {e}"),
+            ConfigError::TypesFile(path, e) => {
+                write!(f, "loading types-file {}: {e}", path.display())
+            }
+        }
+    }
+}
+
+#[test]
+fn parse_config_test() {
+    let config: Config = toml::from_str(
+        r#"
+        forbidden-words = ["foo", "bar"]
+        forbidden-words-in-body = true
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.forbidden_words, vec!["foo", "bar"]);
+    assert!(config.forbidden_words_in_body);
+
+    let config: Config = toml::from_str("").unwrap();
+    assert!(config.forbidden_words.is_empty());
+    assert_eq!(config.sections["feat"], "Features");
+
+    let config: Config = toml::from_str(r#"sections = { feat = "New" }"#).unwrap();
+    assert_eq!(config.sections.len(), 1);
+    assert_eq!(config.sections["feat"], "New");
+
+    assert!(toml::from_str::<Config>("unknown-key = 1").is_err());
+
+    let config: Config = toml::from_str(r#"types = ["feat", "wip"]"#).unwrap();
+    assert_eq!(config.types.len(), 2);
+    assert_eq!(config.types["wip"], None);
+    let config: Config = toml::from_str(r#"types = { feat = "A new feature" }"#).unwrap();
+    assert_eq!(config.types["feat"].as_deref(), Some("A new feature"));
+    assert!(toml::from_str::<Config>("types = 1").is_err());
+
+    let config: Config =
+        toml::from_str(r#"package-configs = { "packages/a" = "packages/a/commitlint.toml" }"#)
+            .unwrap();
+    assert_eq!(
+        config.package_configs["packages/a"],
+        Path::new("packages/a/commitlint.toml")
+    );
+
+    let config: Config = toml::from_str(
+        r#"
+        forbid-closing-keywords-in-body = true
+        closing-keywords = ["Close"]
+        "#,
+    )
+    .unwrap();
+    assert!(config.forbid_closing_keywords_in_body);
+    assert_eq!(config.closing_keywords, ["Close"]);
+
+    let config: Config = toml::from_str(r#"comment-char = ";""#).unwrap();
+    assert_eq!(config.comment_char, Some(';'));
+    assert!(toml::from_str::<Config>(r#"comment-char = "//""#).is_err());
+
+    let config: Config = toml::from_str(r#"sentence-end-allowlist = ["approx."]"#).unwrap();
+    assert_eq!(config.sentence_end_allowlist, ["approx."]);
+
+    let config: Config = toml::from_str("max-top-level-dirs = 3").unwrap();
+    assert_eq!(config.max_top_level_dirs, Some(3));
+
+    let config = Config::default();
+    for claim in ["30% faster", "2x", "1.5 ms", "3 times", "saves 10MB"] {
+        assert!(config.perf_claim_pattern.is_match(claim), "{claim}");
+    }
+    for text in ["faster", "v2 api", "x86"] {
+        assert!(!config.perf_claim_pattern.is_match(text), "{text}");
+    }
+    let config: Config = toml::from_str(r#"perf-claim-pattern = '\d+ fps'"#).unwrap();
+    assert!(config.perf_claim_pattern.is_match("60 fps"));
+    assert!(toml::from_str::<Config>(r#"perf-claim-pattern = '('"#).is_err());
+
+    let config: Config = toml::from_str("header-max-length = 72").unwrap();
+    assert_eq!(config.header_max_length, Some(72));
+    let config: Config = toml::from_str("subject-max-length = 50").unwrap();
+    assert_eq!(config.subject_max_length, Some(50));
+    let config: Config = toml::from_str("subject-min-length = 3").unwrap();
+    assert_eq!(config.subject_min_length, Some(3));
+
+    let config: Config = toml::from_str("max-footers = 3").unwrap();
+    assert_eq!(config.max_footers, Some(3));
+    let config: Config = toml::from_str("max-footers = true").unwrap();
+    assert_eq!(config.max_footers, Some(DEFAULT_MAX_FOOTERS));
+    let config: Config = toml::from_str("max-footers = false").unwrap();
+    assert_eq!(config.max_footers, None);
+
+    let config: Config = toml::from_str(
+        r#"
+        issue-reference-style = "jira"
+        issue-reference-patterns = { jira = '\bPROJ-\d+\b' }
+        "#,
+    )
+    .unwrap();
+    assert!(config.validate().is_ok());
+    assert!(config.issue_reference_patterns["jira"].is_match("see PROJ-12"));
+    let config: Config = toml::from_str(r#"issue-reference-style = "jira""#).unwrap();
+    assert!(config.validate().is_err());
+    assert!(toml::from_str::<Config>(r#"issue-reference-patterns = { x = '(' }"#).is_err());
+
+    let config: Config = toml::from_str(
+        r#"
+        severity = { HeaderSubjectEmpty = "warning" }
+        messages.errors = { HeaderSubjectEmpty = "subject is required" }
+        "#,
+    )
+    .unwrap();
+    assert!(config.validate().is_ok());
+    let config: Config = toml::from_str(
+        r#"
+        scope-required-for = ["feat", "fix"]
+        scope-policy = { fix = "required", chore = "forbidden" }
+        "#,
+    )
+    .unwrap();
+    assert!(config.validate().is_ok());
+    let config: Config = toml::from_str(
+        r#"
+        scope-required-for = ["feat", "chore"]
+        scope-policy = { chore = "forbidden" }
+        "#,
+    )
+    .unwrap();
+    assert!(config.validate().is_err());
+    let config: Config =
+        toml::from_str(r#"severity = { HeaderSubjectEpmty = "warning" }"#).unwrap();
+    assert!(config.validate().is_err());
+    let config: Config =
+        toml::from_str(r#"messages.errors = { header-subject-empty = "x" }"#).unwrap();
+    assert!(config.validate().is_err());
+
+    let config: Config =
+        toml::from_str(r#"scope-policy = { feat = "required", chore = "forbidden" }"#).unwrap();
+    assert_eq!(config.scope_policy["feat"], ScopePolicy::Required);
+    assert_eq!(config.scope_policy["chore"], ScopePolicy::Forbidden);
+    assert!(toml::from_str::<Config>(r#"scope-policy = { feat = "always" }"#).is_err());
+    let config: Config = toml::from_str(r#"scope-required-for = ["feat", "fix"]"#).unwrap();
+    assert_eq!(config.scope_required_for, ["feat", "fix"]);
+
+    let config: Config = toml::from_str(
+        r#"
+        [messages]
+        banner = "commit {hash} is invalid"
+        errors = { HeaderSubjectEmpty = "subject required" }
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.messages.banner("abc"), "commit abc is invalid");
+    assert_eq!(
+        config.messages.edit_banner(),
+        "we found errors in commit message:"
+    );
+    assert_eq!(
+        config.messages.errors["HeaderSubjectEmpty"],
+        "subject required"
+    );
+
+    let config: Config =
+        toml::from_str(r#"severity = { HeaderSubjectEmpty = "warning" }"#).unwrap();
+    assert_eq!(config.severity["HeaderSubjectEmpty"], Severity::Warning);
+    assert!(toml::from_str::<Config>(r#"severity = { HeaderSubjectEmpty = "info" }"#).is_err());
+}
+
+#[test]
+fn types_file_test() {
+    let dir = crate::git::test_dir("types-file");
+    let config_path = dir.join(CONFIG_FILE_NAME);
+    let load = |types_file: &str| {
+        std::fs::write(&config_path, format!("types-file = {types_file:?}")).unwrap();
+        Config::load_file(&config_path)
+    };
+
+    std::fs::create_dir_all(dir.join("vendor")).unwrap();
+    std::fs::write(dir.join("vendor/types.json"), r#"["feat", "fix"]"#).unwrap();
+    let config = load("vendor/types.json").unwrap();
+    assert_eq!(config.types.keys().collect::<Vec<_>>(), ["feat", "fix"]);
+
+    std::fs::write(
+        dir.join("vendor/types.toml"),
+        r#"types = { feat = "A new feature" }"#,
+    )
+    .unwrap();
+    let config = load("vendor/types.toml").unwrap();
+    assert_eq!(config.types["feat"].as_deref(), Some("A new feature"));
+
+    std::fs::write(dir.join("vendor/broken.json"), r#"{ "feat": 1 }"#).unwrap();
+    assert!(matches!(
+        load("vendor/broken.json"),
+        Err(ConfigError::TypesFile(..))
+    ));
+    assert!(matches!(
+        load("vendor/missing.json"),
+        Err(ConfigError::TypesFile(..))
+    ));
+
+    std::fs::write(
+        &config_path,
+        "types-file = \"vendor/types.json\"\ntypes = { feat = \"x\" }",
+    )
+    .unwrap();
+    assert!(matches!(
+        Config::load_file(&config_path),
+        Err(ConfigError::Invalid(_))
+    ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn json_schema_test() {
+    let schema = serde_json::from_str::<serde_json::Value>(&json_schema()).unwrap();
+    assert_eq!(schema["additionalProperties"], false);
+    let properties = &schema["properties"];
+    assert_eq!(properties["profile"]["default"], "default");
+    assert_eq!(properties["strip-comments"]["default"], true);
+    assert_eq!(properties["strip-conflicts"]["default"], false);
+    assert!(properties["max-footers"].is_object());
+    assert!(properties["issue-reference-patterns"].is_object());
+}
+
+#[test]
+fn find_config_file_test() {
+    let dir = crate::git::test_dir("find-config");
+    std::fs::create_dir_all(dir.join(".git")).unwrap();
+    std::fs::create_dir_all(dir.join("sub/dir")).unwrap();
+    assert_eq!(
+        find_config_file(&dir.join("sub/dir")),
+        dir.join(CONFIG_FILE_NAME)
+    );
+    assert_eq!(find_config_file(&dir), dir.join(CONFIG_FILE_NAME));
+
+    // linked worktrees and submodules have .git file
+    std::fs::write(dir.join("sub/.git"), "gitdir: ../.git").unwrap();
+    assert_eq!(
+        find_config_file(&dir.join("sub/dir")),
+        dir.join("sub").join(CONFIG_FILE_NAME)
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn load_test() {
+    let dir = crate::git::test_dir("load-config");
+    std::fs::create_dir_all(dir.join(".git")).unwrap();
+    std::fs::create_dir_all(dir.join("sub")).unwrap();
+    std::fs::write(dir.join(CONFIG_FILE_NAME), "header-max-length = 50\n").unwrap();
+    assert_eq!(
+        Config::load(&dir.join("sub")).unwrap().header_max_length,
+        Some(50)
+    );
+
+    // without config file
+    std::fs::remove_file(dir.join(CONFIG_FILE_NAME)).unwrap();
+    assert_eq!(
+        Config::load(&dir).unwrap().header_max_length,
+        Config::default().header_max_length
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,209 @@
+use crate::config::Config;
+use crate::message::{extra_blank_lines, is_fence, is_list_item, Message};
+
+/// an auto fix with the description to show
+pub(crate) type Fix<'a> = (&'static str, Box<dyn Fn(&str) -> String + 'a>);
+
+/// applies all auto fixes enabled by config
+pub(crate) fn fix_message(message: &str, config: &Config, comment_char: char) -> String {
+    fixes(config, comment_char)
+        .iter()
+        .fold(message.to_string(), |message, (_, fix)| fix(&message))
+}
+
+/// the auto fixes enabled by config in the order to apply
+pub(crate) fn fixes(config: &Config, comment_char: char) -> Vec<Fix<'_>> {
+    let mut fixes = Vec::<Fix>::new();
+    if config.forbid_multiple_blank_lines {
+        fixes.push((
+            "collapse consecutive blank lines",
+            Box::new(collapse_blank_lines),
+        ));
+    }
+    if let Some(width) = config.body_max_line_length {
+        fixes.push((
+            "wrap long body lines",
+            Box::new(move |x| reflow_body(x, width, comment_char)),
+        ));
+    }
+    fixes.push(("end with a newline", Box::new(ensure_trailing_newline)));
+    fixes
+}
+
+/// makes the message end with exactly one newline
+pub(crate) fn ensure_trailing_newline(source: &str) -> String {
+    let mut result = source.trim_end_matches(['\r', '\n']).to_string();
+    result.push('\n');
+    result
+}
+
+/// collapses consecutive blank lines in body into one
+pub(crate) fn collapse_blank_lines(source: &str) -> String {
+    let lines = source.lines().collect::<Vec<_>>();
+    let extra = extra_blank_lines(&lines);
+    let mut result = lines
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !extra.contains(i))
+        .map(|(_, x)| *x)
+        .collect::<Vec<_>>()
+        .join("\n");
+    if source.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+/// hard-wraps body paragraphs longer than `width`.
+///
+/// paragraphs with no over-long lines, list items, indented lines, comments starting with `comment_char`,
+/// fenced code blocks, and footers are kept as is.
+/// words longer than `width` such as URLs are never broken.
+pub(crate) fn reflow_body(source: &str, width: usize, comment_char: char) -> String {
+    let message = Message::parse(source);
+    let body_end = message.footer_start.max(message.body_start);
+    let lines = &message.lines;
+
+    let mut result = Vec::<String>::with_capacity(lines.len());
+    result.extend(lines[..message.body_start].iter().map(|x| x.to_string()));
+
+    let mut in_fence = false;
+    let mut paragraph = Vec::<&str>::new();
+    for &line in &lines[message.body_start..body_end] {
+        let keep = if is_fence(line) {
+            in_fence = !in_fence;
+            true
+        } else {
+            in_fence
+                || line.trim().is_empty()
+                || line.starts_with(char::is_whitespace)
+                || line.starts_with(comment_char)
+                || is_list_item(line)
+        };
+        if keep {
+            flush_paragraph(&mut paragraph, width, &mut result);
+            result.push(line.to_string());
+        } else {
+            paragraph.push(line);
+        }
+    }
+    flush_paragraph(&mut paragraph, width, &mut result);
+
+    result.extend(lines[body_end..].iter().map(|x| x.to_string()));
+
+    let mut result = result.join("\n");
+    if source.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+fn flush_paragraph(paragraph: &mut Vec<&str>, width: usize, result: &mut Vec<String>) {
+    if paragraph.iter().all(|x| x.chars().count() <= width) {
+        result.extend(paragraph.iter().map(|x| x.to_string()));
+    } else {
+        let mut line = String::new();
+        let mut line_len = 0;
+        for word in paragraph.iter().flat_map(|x| x.split_whitespace()) {
+            let word_len = word.chars().count();
+            if line_len != 0 && line_len + 1 + word_len > width {
+                result.push(std::mem::take(&mut line));
+                line_len = 0;
+            }
+            if line_len != 0 {
+                line.push(' ');
+                line_len += 1;
+            }
+            line.push_str(word);
+            line_len += word_len;
+        }
+        if line_len != 0 {
+            result.push(line);
+        }
+    }
+    paragraph.clear();
+}
+
+#[test]
+fn collapse_blank_lines_test() {
+    assert_eq!(
+        collapse_blank_lines("feat: x\n\n\nbody\n"),
+        "feat: x\n\nbody\n"
+    );
+    assert_eq!(
+        collapse_blank_lines("feat: x\n\nbody\n\n\n\nnext\n"),
+        "feat: x\n\nbody\n\nnext\n"
+    );
+    assert_eq!(
+        collapse_blank_lines("feat: x\n\n```\n\n\n```\n"),
+        "feat: x\n\n```\n\n\n```\n"
+    );
+}
+
+#[test]
+fn ensure_trailing_newline_test() {
+    for source in [
+        "feat: x\n\nbody",
+        "feat: x\n\nbody\n",
+        "feat: x\n\nbody\n\n\n",
+    ] {
+        let fixed = ensure_trailing_newline(source);
+        assert_eq!(fixed, "feat: x\n\nbody\n");
+        assert_eq!(ensure_trailing_newline(&fixed), fixed);
+    }
+    assert_eq!(
+        fix_message("feat: x\r\n\r\n", &Config::default(), '#'),
+        "feat: x\n"
+    );
+}
+
+#[test]
+fn fixes_test() {
+    let descriptions = |config: &Config| fixes(config, '#').iter().map(|x| x.0).collect::<Vec<_>>();
+    assert_eq!(descriptions(&Config::default()), ["end with a newline"]);
+    let config = Config {
+        forbid_multiple_blank_lines: true,
+        body_max_line_length: Some(20),
+        ..Config::default()
+    };
+    assert_eq!(
+        descriptions(&config),
+        [
+            "collapse consecutive blank lines",
+            "wrap long body lines",
+            "end with a newline"
+        ]
+    );
+    assert_eq!(
+        fix_message(
+            "feat: x\n\n\nthis is a long line to be wrapped",
+            &config,
+            '#'
+        ),
+        "feat: x\n\nthis is a long line\nto be wrapped\n"
+    );
+}
+
+#[test]
+fn reflow_body_test() {
+    macro_rules! test {
+        ($source: literal => $expected: literal) => {
+            assert_eq!(reflow_body($source, 20, '#'), $expected);
+            // reflowing is idempotent
+            assert_eq!(reflow_body($expected, 20, '#'), $expected);
+        };
+    }
+
+    // already wrapped
+    test!("feat: a very long header which is not wrapped\n\nshort\nlines\n" => "feat: a very long header which is not wrapped\n\nshort\nlines\n");
+    // wrapped
+    test!("feat: x\n\nthis is a long line to be wrapped\n" => "feat: x\n\nthis is a long line\nto be wrapped\n");
+    // paragraphs are not merged
+    test!("feat: x\n\nthis is a long line to be wrapped\n\nnext paragraph\n" => "feat: x\n\nthis is a long line\nto be wrapped\n\nnext paragraph\n");
+    // urls are not broken
+    test!("feat: x\n\nsee https://example.com/very/long/url\n" => "feat: x\n\nsee\nhttps://example.com/very/long/url\n");
+    // list items and code blocks are kept
+    test!("feat: x\n\n- this is a long list item\n```\nthis is a long code line\n```\n" => "feat: x\n\n- this is a long list item\n```\nthis is a long code line\n```\n");
+    // footers are kept
+    test!("feat: x\n\nbody\n\nReviewed-by: Long Name <long@example.com>" => "feat: x\n\nbody\n\nReviewed-by: Long Name <long@example.com>");
+}
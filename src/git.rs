@@ -1,36 +1,84 @@
 use hex::FromHexError;
 use std::fmt::{Debug, Display, Formatter};
 use std::io;
+#[cfg(test)]
+use std::path::Path;
 use std::path::PathBuf;
 use std::process::Stdio;
-use std::str::FromStr;
+use std::str::{FromStr, Utf8Error};
+use std::sync::OnceLock;
 use tokio::process::Command;
 
-#[derive(Copy, Clone)]
-pub struct ObjectHash([u8; ObjectHash::HASH_BYTES]);
+/// the object format of the repository
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum HashAlgorithm {
+    Sha1,
+    Sha256,
+}
 
-impl ObjectHash {
-    pub const HASH_BITS: usize = 160;
-    pub const HASH_BYTES: usize = Self::HASH_BITS / 8;
-    pub const HASH_HEX_LEN: usize = Self::HASH_BITS / 4;
+impl HashAlgorithm {
+    pub fn hex_len(self) -> usize {
+        match self {
+            HashAlgorithm::Sha1 => 40,
+            HashAlgorithm::Sha256 => 64,
+        }
+    }
+}
+
+/// the object name in SHA-1 or SHA-256 repository
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum ObjectHash {
+    Sha1([u8; 20]),
+    Sha256([u8; 32]),
+}
 
+impl ObjectHash {
+    /// parses the hex object name. the algorithm is inferred from the length
     pub fn from_hex(s: impl AsRef<[u8]>) -> Result<ObjectHash, FromHexError> {
         let s = s.as_ref();
-        if s.len() != Self::HASH_HEX_LEN {
+        match s.len() {
+            40 => {
+                let mut buf = [0u8; 20];
+                hex::decode_to_slice(s, &mut buf[..])?;
+                Ok(Self::Sha1(buf))
+            }
+            64 => {
+                let mut buf = [0u8; 32];
+                hex::decode_to_slice(s, &mut buf[..])?;
+                Ok(Self::Sha256(buf))
+            }
+            _ => Err(FromHexError::InvalidStringLength),
+        }
+    }
+
+    /// parses the hex object name of the algorithm
+    pub fn from_hex_as(
+        s: impl AsRef<[u8]>,
+        algorithm: HashAlgorithm,
+    ) -> Result<ObjectHash, FromHexError> {
+        let s = s.as_ref();
+        if s.len() != algorithm.hex_len() {
             return Err(FromHexError::InvalidStringLength);
         }
-        let mut buf = [0u8; Self::HASH_BYTES];
-        hex::decode_to_slice(s, &mut buf[..])?;
-        Ok(Self(buf))
+        Self::from_hex(s)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Sha1(bytes) => bytes,
+            Self::Sha256(bytes) => bytes,
+        }
     }
 }
 
 impl Display for ObjectHash {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let mut buf = [0u8; Self::HASH_HEX_LEN];
-        hex::encode_to_slice(&self.0, &mut buf).expect("encoding hex");
+        let bytes = self.as_bytes();
+        let mut buf = [0u8; 64];
+        let buf = &mut buf[..bytes.len() * 2];
+        hex::encode_to_slice(bytes, buf).expect("encoding hex");
         // SAFETY: hex is utf8
-        f.write_str(unsafe { std::str::from_utf8_unchecked(&buf[..]) })
+        f.write_str(unsafe { std::str::from_utf8_unchecked(buf) })
     }
 }
 
@@ -48,16 +96,40 @@ impl FromStr for ObjectHash {
     }
 }
 
+/// parses the first whitespace-delimited token of the output as a hash
+fn parse_hash_output(stdout: &[u8], algorithm: HashAlgorithm) -> Option<ObjectHash> {
+    let token = stdout
+        .split(|x| x.is_ascii_whitespace())
+        .find(|x| !x.is_empty())?;
+    ObjectHash::from_hex_as(token, algorithm).ok()
+}
+
 pub(crate) struct GitRepository {
     cwd: PathBuf,
+    /// environment variables set in addition to inherited ones
+    envs: Vec<(String, String)>,
+    /// the cache of `object_format`
+    object_format: OnceLock<HashAlgorithm>,
 }
 
 impl GitRepository {
     pub(crate) fn new_cwd() -> Self {
+        Self::new(PathBuf::from("."))
+    }
+
+    /// the repository containing `cwd`. relative path is resolved against the current directory
+    pub(crate) fn new(cwd: PathBuf) -> Self {
         Self {
-            cwd: PathBuf::from("."),
+            cwd,
+            envs: Vec::new(),
+            object_format: OnceLock::new(),
         }
     }
+
+    /// sets environment variable for git commands like `GIT_ALTERNATE_OBJECT_DIRECTORIES`.
+    pub(crate) fn add_env(&mut self, key: String, value: String) {
+        self.envs.push((key, value));
+    }
 }
 
 impl GitRepository {
@@ -65,46 +137,262 @@ impl GitRepository {
         let mut command = Command::new("git");
         command.arg(subcommand);
         command.current_dir(&self.cwd);
+        // note: environment is inherited so git configuration variables are available
+        command.envs(self.envs.iter().map(|(k, v)| (k, v)));
         command
     }
 
+    /// returns the object format of the repository. the result is cached
+    pub async fn object_format(&self) -> io::Result<HashAlgorithm> {
+        if let Some(&algorithm) = self.object_format.get() {
+            return Ok(algorithm);
+        }
+        let output = self
+            .command("rev-parse")
+            .arg("--show-object-format")
+            .stdout(Stdio::piped())
+            .spawn()?
+            .wait_with_output()
+            .await?;
+        // git before 2.25 doesn't know the option but supports only SHA-1
+        let algorithm = match output.stdout.trim_ascii() {
+            b"sha256" => HashAlgorithm::Sha256,
+            _ => HashAlgorithm::Sha1,
+        };
+        Ok(*self.object_format.get_or_init(|| algorithm))
+    }
+
+    /// resolves the name to an object hash. returns `None` without error message if not found
     pub async fn rev_parse(&self, name: &str) -> io::Result<Option<ObjectHash>> {
         let output = self
             .command("rev-parse")
+            .arg("--verify")
+            .arg("--quiet")
             .arg(name)
             .stdout(Stdio::piped())
             .spawn()?
             .wait_with_output()
             .await?;
-        if !output.status.success() || output.stdout.len() < ObjectHash::HASH_HEX_LEN {
+        if !output.status.success() {
+            return Ok(None);
+        }
+        Ok(parse_hash_output(
+            &output.stdout,
+            self.object_format().await?,
+        ))
+    }
+
+    /// returns the best common ancestor of two commits
+    pub async fn merge_base(&self, a: ObjectHash, b: ObjectHash) -> io::Result<Option<ObjectHash>> {
+        let output = self
+            .command("merge-base")
+            .arg(format!("{a}"))
+            .arg(format!("{b}"))
+            .stdout(Stdio::piped())
+            .spawn()?
+            .wait_with_output()
+            .await?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        Ok(parse_hash_output(
+            &output.stdout,
+            self.object_format().await?,
+        ))
+    }
+
+    /// returns the hooks directory. this respects the common directory of linked worktrees,
+    /// the git directory of submodules, and `core.hooksPath`
+    pub async fn hooks_dir(&self) -> io::Result<Option<PathBuf>> {
+        let output = self
+            .command("rev-parse")
+            .arg("--git-path")
+            .arg("hooks")
+            .stdout(Stdio::piped())
+            .spawn()?
+            .wait_with_output()
+            .await?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        let Ok(path) = String::from_utf8(output.stdout) else {
+            return Ok(None);
+        };
+        Ok(Some(self.cwd.join(path.trim_end_matches(['\r', '\n']))))
+    }
+
+    /// returns the root of the working tree, or `None` if not in a working tree
+    pub async fn toplevel(&self) -> io::Result<Option<PathBuf>> {
+        let output = self
+            .command("rev-parse")
+            .arg("--show-toplevel")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?
+            .wait_with_output()
+            .await?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        let Ok(path) = String::from_utf8(output.stdout) else {
+            return Ok(None);
+        };
+        Ok(Some(PathBuf::from(path.trim_end_matches(['\r', '\n']))))
+    }
+
+    /// returns the value of the git configuration variable, or `None` if not set
+    pub async fn config_value(&self, key: &str) -> io::Result<Option<String>> {
+        let output = self
+            .command("config")
+            .arg("--get")
+            .arg(key)
+            .stdout(Stdio::piped())
+            .spawn()?
+            .wait_with_output()
+            .await?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        let value = String::from_utf8_lossy(&output.stdout);
+        Ok(Some(value.trim_end_matches(['\r', '\n']).to_string()))
+    }
+
+    /// returns true if HEAD is not a symbolic ref to a branch
+    pub async fn is_detached_head(&self) -> io::Result<bool> {
+        let output = self
+            .command("symbolic-ref")
+            .arg("-q")
+            .arg("HEAD")
+            .stdout(Stdio::null())
+            .spawn()?
+            .wait_with_output()
+            .await?;
+        // exits with 1 if HEAD is detached and 128 if not in a repository
+        Ok(output.status.code() == Some(1))
+    }
+
+    /// returns the most recent tag reachable from HEAD like `git describe --tags --abbrev=0`
+    pub async fn latest_tag(&self) -> io::Result<Option<String>> {
+        let output = self
+            .command("describe")
+            .arg("--tags")
+            .arg("--abbrev=0")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?
+            .wait_with_output()
+            .await?;
+        if !output.status.success() {
+            // no tags
             return Ok(None);
         }
-        Ok(ObjectHash::from_hex(&output.stdout[..ObjectHash::HASH_HEX_LEN]).ok())
+        let tag = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(Some(tag).filter(|x| !x.is_empty()))
     }
 
+    /// returns tags matching the glob pattern, sorted as versions
+    pub async fn list_tags(&self, pattern: &str) -> io::Result<Vec<String>> {
+        let output = self
+            .command("tag")
+            .arg("--list")
+            .arg("--sort=v:refname")
+            .arg(pattern)
+            .stdout(Stdio::piped())
+            .spawn()?
+            .wait_with_output()
+            .await?;
+        if !output.status.success() {
+            return Ok(Vec::new());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|x| x.trim().to_string())
+            .filter(|x| !x.is_empty())
+            .collect())
+    }
+
+    /// returns commits reachable from `head` but not from `base`
     pub async fn get_commits(
         &self,
         head: ObjectHash,
         base: ObjectHash,
+        options: LogOptions,
     ) -> io::Result<Vec<ObjectHash>> {
-        let output = self
-            .command("log")
-            .arg("--format=%H")
+        let exclude = if options.include_base {
+            // exclude the parents of base instead of base
+            format!("^{base}^@")
+        } else {
+            format!("^{base}")
+        };
+        self.log_hashes(head, Some(exclude), options).await
+    }
+
+    /// returns all commits reachable from `head` with the same options as `get_commits`.
+    /// `include_base` is ignored since there is no base
+    pub async fn get_all_commits(
+        &self,
+        head: ObjectHash,
+        options: LogOptions,
+    ) -> io::Result<Vec<ObjectHash>> {
+        self.log_hashes(head, None, options).await
+    }
+
+    async fn log_hashes(
+        &self,
+        head: ObjectHash,
+        exclude: Option<String>,
+        options: LogOptions,
+    ) -> io::Result<Vec<ObjectHash>> {
+        let mut command = self.command("log");
+        command.arg("--format=%H");
+        if options.no_merges {
+            command.arg("--no-merges");
+        }
+        if options.first_parent {
+            command.arg("--first-parent");
+        }
+        let output = command
             .arg(format!("{head}"))
-            .arg(format!("^{base}"))
+            .args(exclude)
             .stdout(Stdio::piped())
             .spawn()?
             .wait_with_output()
             .await?;
-        if !output.status.success() || output.stdout.len() < ObjectHash::HASH_HEX_LEN {
+        if !output.status.success() {
             return Ok(Vec::new());
         }
+        let algorithm = self.object_format().await?;
         let stdout = output.stdout.as_slice();
         Ok(stdout
             .split(|x: &u8| *x == b'\n')
             .map(|x| x.strip_suffix(b"\r").unwrap_or(x))
             .filter(|x| !x.is_empty())
-            .filter_map(|x| ObjectHash::from_hex(x).ok())
+            .filter_map(|x| ObjectHash::from_hex_as(x, algorithm).ok())
+            .collect())
+    }
+
+    /// returns the paths changed by the commit compared to its first parent
+    pub async fn changed_files(&self, hash: ObjectHash) -> io::Result<Vec<String>> {
+        let output = self
+            .command("diff-tree")
+            .arg("--no-commit-id")
+            .arg("--name-only")
+            .arg("-r")
+            .arg("--root")
+            .arg("-m")
+            .arg("--first-parent")
+            .arg(format!("{hash}"))
+            .stdout(Stdio::piped())
+            .spawn()?
+            .wait_with_output()
+            .await?;
+        if !output.status.success() {
+            return Ok(Vec::new());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|x| !x.is_empty())
+            .map(|x| x.to_string())
             .collect())
     }
 
@@ -125,6 +413,18 @@ impl GitRepository {
     }
 }
 
+/// the options to list commits with `get_commits` and `get_all_commits`
+#[derive(Debug, Copy, Clone, Default)]
+pub struct LogOptions {
+    /// `base` itself is also included
+    pub include_base: bool,
+    /// merge commits are excluded like `git log --no-merges`
+    pub no_merges: bool,
+    /// only the first parent of merge commits are followed.
+    /// with `no_merges`, the merge commits on the first-parent chain are also excluded.
+    pub first_parent: bool,
+}
+
 #[derive(Debug)]
 pub struct CommitObject {
     pub header: Vec<(Vec<u8>, Vec<u8>)>,
@@ -132,11 +432,19 @@ pub struct CommitObject {
 }
 
 impl CommitObject {
+    /// creates a commit object known only by message like commits in GitHub event payloads
+    pub fn from_message(message: Vec<u8>) -> CommitObject {
+        CommitObject {
+            header: Vec::new(),
+            message,
+        }
+    }
+
     pub fn parse(mut source: &[u8]) -> Option<CommitObject> {
         fn try_split(source: &[u8], find: u8) -> Option<(&[u8], &[u8])> {
             let p = source.iter().position(|&b| b == find)?;
             let (before, after) = source.split_at(p);
-            return Some((before, &after[1..]));
+            Some((before, &after[1..]))
         }
 
         #[allow(unused_assignments)] // to avoid intellij bug
@@ -144,7 +452,12 @@ impl CommitObject {
 
         let mut header = Vec::<(Vec<u8>, Vec<u8>)>::new();
         loop {
+            if source.is_empty() {
+                // no message and no separator after the headers
+                break;
+            }
             (line, source) = try_split(source, b'\n')?;
+            // the message starts after the first empty line even if it looks like a header
             if line == b"" {
                 break;
             }
@@ -167,4 +480,614 @@ impl CommitObject {
             message: source.to_vec(),
         })
     }
+
+    /// the message as utf8 string
+    pub fn message_str(&self) -> Result<&str, Utf8Error> {
+        std::str::from_utf8(&self.message)
+    }
+
+    /// the hashes in `parent` headers
+    pub fn parents(&self) -> Vec<ObjectHash> {
+        self.header
+            .iter()
+            .filter(|(x, _)| x == b"parent")
+            .filter_map(|(_, x)| ObjectHash::from_hex(x).ok())
+            .collect()
+    }
+
+    /// returns true if the commit has multiple parents
+    pub fn is_merge(&self) -> bool {
+        self.parents().len() > 1
+    }
+
+    /// the first line of the message
+    pub fn subject(&self) -> &[u8] {
+        let line = self.message.split(|&x| x == b'\n').next().unwrap();
+        line.strip_suffix(b"\r").unwrap_or(line)
+    }
+}
+
+#[test]
+fn object_hash_test() {
+    let sha1 = "e2f8b41fc7543b5e3b30de7543ec851a86ddd57c";
+    let sha256 = "6d3b8c4cbd5b6f3a7a1f0ec1f7d1d4e7c9e3a6b2f18e4f4d2a9c9f0e1b2c3d4e";
+    let hash = ObjectHash::from_hex(sha1).unwrap();
+    assert!(matches!(hash, ObjectHash::Sha1(_)));
+    assert_eq!(hash.to_string(), sha1);
+    let hash = ObjectHash::from_hex(sha256).unwrap();
+    assert!(matches!(hash, ObjectHash::Sha256(_)));
+    assert_eq!(hash.to_string(), sha256);
+    assert_eq!(hash.as_bytes().len(), 32);
+    assert!(ObjectHash::from_hex(&sha256[..50]).is_err());
+    assert!(ObjectHash::from_hex("").is_err());
+    assert!(ObjectHash::from_hex_as(sha256, HashAlgorithm::Sha256).is_ok());
+    assert!(ObjectHash::from_hex_as(sha256, HashAlgorithm::Sha1).is_err());
+}
+
+#[test]
+fn parse_hash_output_test() {
+    let hash = "e2f8b41fc7543b5e3b30de7543ec851a86ddd57c";
+    let expected = ObjectHash::from_hex(hash).ok();
+    assert_eq!(
+        parse_hash_output(hash.as_bytes(), HashAlgorithm::Sha1),
+        expected
+    );
+    assert_eq!(
+        parse_hash_output(format!("{hash}\n").as_bytes(), HashAlgorithm::Sha1),
+        expected
+    );
+    assert_eq!(
+        parse_hash_output(format!("{hash}\r\n").as_bytes(), HashAlgorithm::Sha1),
+        expected
+    );
+    assert_eq!(
+        parse_hash_output(format!("  {hash} \n").as_bytes(), HashAlgorithm::Sha1),
+        expected
+    );
+    assert_eq!(parse_hash_output(b"e2f8b41\n", HashAlgorithm::Sha1), None);
+    assert_eq!(parse_hash_output(b"\n", HashAlgorithm::Sha1), None);
+    assert_eq!(parse_hash_output(b"HEAD\n", HashAlgorithm::Sha1), None);
+    assert_eq!(
+        parse_hash_output(hash.as_bytes(), HashAlgorithm::Sha256),
+        None
+    );
+}
+
+#[test]
+fn commit_object_test() {
+    let commit = CommitObject::parse(
+        b"tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\
+        author a <a@a> 0 +0000\n\
+        committer a <a@a> 0 +0000\n\
+        \n\
+        feat: subject\r\n\
+        \n\
+        body\n",
+    )
+    .unwrap();
+    assert_eq!(commit.header.len(), 3);
+    assert!(commit.parents().is_empty());
+    assert_eq!(commit.subject(), b"feat: subject");
+    assert_eq!(commit.message_str(), Ok("feat: subject\r\n\nbody\n"));
+    assert!(!commit.is_merge());
+
+    // merge with a custom message
+    let commit = CommitObject::parse(
+        b"tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\
+        parent e2f8b41fc7543b5e3b30de7543ec851a86ddd57c\n\
+        parent 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\
+        \n\
+        sync with upstream\n",
+    )
+    .unwrap();
+    assert_eq!(
+        commit.parents(),
+        [
+            ObjectHash::from_hex("e2f8b41fc7543b5e3b30de7543ec851a86ddd57c").unwrap(),
+            ObjectHash::from_hex("4b825dc642cb6eb9a060e54bf8d69288fbee4904").unwrap(),
+        ]
+    );
+    assert!(commit.is_merge());
+
+    let commit =
+        CommitObject::parse(b"tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\n\xff").unwrap();
+    assert!(commit.message_str().is_err());
+
+    // the message looks like headers
+    let commit = CommitObject::parse(
+        b"tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\
+        gpgsig -----BEGIN PGP SIGNATURE-----\n\
+        \x20\n\
+        \x20-----END PGP SIGNATURE-----\n\
+        \n\
+        tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\
+        \n\
+        parent x\n",
+    )
+    .unwrap();
+    assert_eq!(commit.header.len(), 2);
+    assert_eq!(
+        commit.header[1].1,
+        b"-----BEGIN PGP SIGNATURE-----\n\n-----END PGP SIGNATURE-----"
+    );
+    assert_eq!(
+        commit.subject(),
+        b"tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904"
+    );
+    assert_eq!(
+        commit.message,
+        b"tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\nparent x\n"
+    );
+    assert!(!commit.is_merge());
+
+    // no message
+    let commit = CommitObject::parse(b"tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n").unwrap();
+    assert_eq!(commit.header.len(), 1);
+    assert!(commit.message.is_empty());
+    assert!(CommitObject::parse(b"tree").is_none());
+}
+
+#[cfg(test)]
+pub(crate) fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "conventional-commitlint-test-{}-{name}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("creating test directory");
+    dir
+}
+
+#[cfg(test)]
+pub(crate) fn run_git(dir: &Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .expect("running git");
+    assert!(status.success(), "git {args:?} failed");
+}
+
+/// creates a repository on `main` branch with an empty commit for each message, oldest first.
+/// returns `None` to skip the test if `git` is not available
+#[cfg(test)]
+pub(crate) fn test_repo(name: &str, messages: &[&str]) -> Option<PathBuf> {
+    let available = std::process::Command::new("git")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .status()
+        .is_ok_and(|x| x.success());
+    if !available {
+        eprintln!("git is not available: skipping");
+        return None;
+    }
+    let dir = test_dir(name);
+    run_git(&dir, &["init", "-q", "-b", "main"]);
+    for message in messages {
+        run_git(&dir, &["commit", "-q", "--allow-empty", "-m", message]);
+    }
+    Some(dir)
+}
+
+/// commits `files` with the message as their contents
+#[cfg(test)]
+pub(crate) fn commit_files(dir: &Path, files: &[&str], message: &str) {
+    for file in files {
+        let path = dir.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, message).unwrap();
+        run_git(dir, &["add", file]);
+    }
+    run_git(dir, &["commit", "-q", "-m", message]);
+}
+
+#[tokio::test]
+async fn test_repo_test() {
+    let Some(dir) = test_repo(
+        "fixture",
+        &[
+            "chore: init",
+            "feat: good",
+            "bad message",
+            "fix: another\n\nbody",
+        ],
+    ) else {
+        return;
+    };
+
+    let git = GitRepository::new(dir.clone());
+    let head = git.rev_parse("HEAD").await.unwrap().unwrap();
+    assert_eq!(git.rev_parse("no-such-ref").await.unwrap(), None);
+    assert_eq!(
+        git.toplevel()
+            .await
+            .unwrap()
+            .map(|x| x.canonicalize().unwrap()),
+        Some(dir.canonicalize().unwrap())
+    );
+
+    // a commit for each message
+    let commits = git
+        .get_all_commits(head, LogOptions::default())
+        .await
+        .unwrap();
+    let mut subjects = vec![];
+    for hash in commits {
+        let commit = git.get_commit(hash).await.unwrap().unwrap();
+        subjects.push(String::from_utf8(commit.subject().to_vec()).unwrap());
+    }
+    assert_eq!(
+        subjects,
+        ["fix: another", "bad message", "feat: good", "chore: init"]
+    );
+    let commit = git.get_commit(head).await.unwrap().unwrap();
+    assert_eq!(commit.message_str().unwrap(), "fix: another\n\nbody\n");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn rev_parse_test() {
+    let Some(dir) = test_repo("rev-parse", &["chore: init", "feat: first"]) else {
+        return;
+    };
+    run_git(&dir, &["tag", "-a", "-m", "release", "v1.0.0"]);
+    // a tag and a branch of the same name
+    run_git(&dir, &["tag", "same", "HEAD~1"]);
+    run_git(&dir, &["branch", "same"]);
+
+    let git = GitRepository::new(dir.clone());
+    let head = git.rev_parse("HEAD").await.unwrap().unwrap();
+    let first = git.rev_parse("HEAD~1").await.unwrap().unwrap();
+    assert_eq!(git.rev_parse("HEAD^{commit}").await.unwrap(), Some(head));
+    // annotated tags are peeled only with ^{commit}
+    let tag = git.rev_parse("v1.0.0").await.unwrap().unwrap();
+    assert_ne!(tag, head);
+    assert_eq!(git.rev_parse("v1.0.0^{commit}").await.unwrap(), Some(head));
+    // ambiguous names resolve to the tag like git does, without a warning
+    assert_eq!(git.rev_parse("same").await.unwrap(), Some(first));
+    assert_eq!(git.rev_parse("heads/same").await.unwrap(), Some(head));
+    // --verify accepts a single revision only
+    assert_eq!(git.rev_parse("HEAD~1..HEAD").await.unwrap(), None);
+    assert_eq!(git.rev_parse("HEAD~5").await.unwrap(), None);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn hooks_dir_in_worktree_test() {
+    let Some(dir) = test_repo("worktree", &["chore: init"]) else {
+        return;
+    };
+    run_git(&dir, &["worktree", "add", "-q", "linked"]);
+
+    let main_hooks = GitRepository::new(dir.clone())
+        .hooks_dir()
+        .await
+        .unwrap()
+        .unwrap();
+    let linked_hooks = GitRepository::new(dir.join("linked"))
+        .hooks_dir()
+        .await
+        .unwrap()
+        .unwrap();
+
+    std::fs::create_dir_all(&main_hooks).unwrap();
+    // hooks of linked worktree are in the common directory
+    assert_eq!(
+        main_hooks.canonicalize().unwrap(),
+        linked_hooks.canonicalize().unwrap()
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn alternate_object_directory_test() {
+    let Some(dir) = test_repo("alternates", &["feat: add feature"]) else {
+        return;
+    };
+
+    let mut git = GitRepository::new(dir.clone());
+    let head = git.rev_parse("HEAD").await.unwrap().unwrap();
+
+    // move all objects to alternate directory
+    let alternate = dir.join("alternate");
+    std::fs::rename(dir.join(".git/objects"), &alternate).unwrap();
+    std::fs::create_dir(dir.join(".git/objects")).unwrap();
+    assert!(git.get_commit(head).await.unwrap().is_none());
+
+    let alternate = alternate.canonicalize().unwrap();
+    git.add_env(
+        "GIT_ALTERNATE_OBJECT_DIRECTORIES".to_string(),
+        alternate.to_str().unwrap().to_string(),
+    );
+    let commit = git.get_commit(head).await.unwrap().unwrap();
+    assert_eq!(commit.subject(), b"feat: add feature");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn get_commits_include_base_test() {
+    let Some(dir) = test_repo(
+        "include-base",
+        &["chore: init", "feat: first", "fix: second"],
+    ) else {
+        return;
+    };
+
+    let git = GitRepository::new(dir.clone());
+    let head = git.rev_parse("HEAD").await.unwrap().unwrap();
+    let base = git.rev_parse("HEAD~1").await.unwrap().unwrap();
+    let root = git.rev_parse("HEAD~2").await.unwrap().unwrap();
+
+    let commits = git
+        .get_commits(head, base, LogOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(commits, vec![head]);
+    let commits = git
+        .get_commits(
+            head,
+            base,
+            LogOptions {
+                include_base: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(commits, vec![head, base]);
+    // root commit has no parents to exclude
+    let commits = git
+        .get_commits(
+            head,
+            root,
+            LogOptions {
+                include_base: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(commits, vec![head, base, root]);
+    // single commit
+    let commits = git
+        .get_commits(
+            head,
+            head,
+            LogOptions {
+                include_base: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(commits, vec![head]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn get_commits_merges_test() {
+    let Some(dir) = test_repo("merges", &["chore: init"]) else {
+        return;
+    };
+    run_git(&dir, &["checkout", "-q", "-b", "topic"]);
+    run_git(
+        &dir,
+        &["commit", "-q", "--allow-empty", "-m", "feat: topic"],
+    );
+    run_git(&dir, &["checkout", "-q", "main"]);
+    run_git(&dir, &["commit", "-q", "--allow-empty", "-m", "fix: main"]);
+    run_git(
+        &dir,
+        &[
+            "merge",
+            "-q",
+            "--no-ff",
+            "-m",
+            "Merge branch 'topic'",
+            "topic",
+        ],
+    );
+
+    let git = GitRepository::new(dir.clone());
+    let head = git.rev_parse("HEAD").await.unwrap().unwrap();
+    let root = git.rev_parse("HEAD~2").await.unwrap().unwrap();
+
+    let commits = git
+        .get_commits(head, root, LogOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(commits.len(), 3);
+    assert!(commits.contains(&head));
+    let commits = git
+        .get_commits(
+            head,
+            root,
+            LogOptions {
+                no_merges: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(commits.len(), 2);
+    assert!(!commits.contains(&head));
+
+    let main = git.rev_parse("HEAD^1").await.unwrap().unwrap();
+    let commits = git
+        .get_commits(
+            head,
+            root,
+            LogOptions {
+                first_parent: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(commits, vec![head, main]);
+    let commits = git
+        .get_commits(
+            head,
+            root,
+            LogOptions {
+                no_merges: true,
+                first_parent: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(commits, vec![main]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn merge_base_test() {
+    let Some(dir) = test_repo("merge-base", &["chore: init"]) else {
+        return;
+    };
+    run_git(&dir, &["checkout", "-q", "-b", "topic"]);
+    run_git(
+        &dir,
+        &["commit", "-q", "--allow-empty", "-m", "feat: topic"],
+    );
+    run_git(&dir, &["checkout", "-q", "main"]);
+    run_git(&dir, &["commit", "-q", "--allow-empty", "-m", "fix: main"]);
+
+    let git = GitRepository::new(dir.clone());
+    let main = git.rev_parse("main").await.unwrap().unwrap();
+    let topic = git.rev_parse("topic").await.unwrap().unwrap();
+    let root = git.rev_parse("main~1").await.unwrap().unwrap();
+    assert_eq!(git.merge_base(topic, main).await.unwrap(), Some(root));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn is_detached_head_test() {
+    let Some(dir) = test_repo("detached-head", &["chore: init"]) else {
+        return;
+    };
+
+    let git = GitRepository::new(dir.clone());
+    assert!(!git.is_detached_head().await.unwrap());
+    run_git(&dir, &["checkout", "-q", "--detach"]);
+    assert!(git.is_detached_head().await.unwrap());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn config_value_test() {
+    let Some(dir) = test_repo("config-value", &[]) else {
+        return;
+    };
+    run_git(&dir, &["config", "core.commentChar", ";"]);
+
+    let git = GitRepository::new(dir.clone());
+    assert_eq!(
+        git.config_value("core.commentChar").await.unwrap(),
+        Some(";".to_string())
+    );
+    assert_eq!(git.config_value("commitlint.unset").await.unwrap(), None);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn changed_files_test() {
+    let Some(dir) = test_repo("changed-files", &[]) else {
+        return;
+    };
+    commit_files(&dir, &["a.txt"], "chore: init");
+    run_git(
+        &dir,
+        &["commit", "-q", "--allow-empty", "-m", "feat: empty"],
+    );
+
+    let git = GitRepository::new(dir.clone());
+    let root = git.rev_parse("HEAD~1").await.unwrap().unwrap();
+    let empty = git.rev_parse("HEAD").await.unwrap().unwrap();
+    assert_eq!(git.changed_files(root).await.unwrap(), vec!["a.txt"]);
+    assert!(git.changed_files(empty).await.unwrap().is_empty());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn sha256_repository_test() {
+    let dir = test_dir("sha256");
+    run_git(&dir, &["init", "-q", "--object-format=sha256"]);
+    for message in ["chore: init", "feat: first"] {
+        run_git(&dir, &["commit", "-q", "--allow-empty", "-m", message]);
+    }
+
+    let git = GitRepository::new(dir.clone());
+    let head = git.rev_parse("HEAD").await.unwrap().unwrap();
+    let base = git.rev_parse("HEAD~1").await.unwrap().unwrap();
+    assert!(matches!(head, ObjectHash::Sha256(_)));
+    assert_eq!(git.object_format().await.unwrap(), HashAlgorithm::Sha256);
+    let commits = git
+        .get_commits(head, base, LogOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(commits, vec![head]);
+    let commit = git.get_commit(head).await.unwrap().unwrap();
+    assert_eq!(commit.subject(), b"feat: first");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn list_tags_test() {
+    let Some(dir) = test_repo("tags", &["chore: init"]) else {
+        return;
+    };
+    for tag in ["v1.10.0", "v1.2.0", "v1.9.0", "other"] {
+        run_git(&dir, &["tag", tag]);
+    }
+
+    let git = GitRepository::new(dir.clone());
+    let tags = git.list_tags("v*").await.unwrap();
+    assert_eq!(tags, vec!["v1.2.0", "v1.9.0", "v1.10.0"]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn latest_tag_test() {
+    let Some(dir) = test_repo("latest-tag", &["chore: init", "feat: first"]) else {
+        return;
+    };
+
+    let git = GitRepository::new(dir.clone());
+    assert_eq!(git.latest_tag().await.unwrap(), None);
+    let head = git.rev_parse("HEAD").await.unwrap().unwrap();
+    assert_eq!(
+        git.get_all_commits(head, LogOptions::default())
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+
+    run_git(&dir, &["tag", "v1.0.0", "HEAD~1"]);
+    run_git(
+        &dir,
+        &["commit", "-q", "--allow-empty", "-m", "fix: second"],
+    );
+    assert_eq!(git.latest_tag().await.unwrap().as_deref(), Some("v1.0.0"));
+    run_git(&dir, &["tag", "-a", "-m", "release", "v1.1.0"]);
+    assert_eq!(git.latest_tag().await.unwrap().as_deref(), Some("v1.1.0"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
 }
@@ -1,76 +1,78 @@
+use crate::config::{Config, Profile, ScopePolicy, Severity};
+use futures_util::StreamExt;
+use std::collections::BTreeMap;
 use std::env::args_os;
+use std::ffi::OsString;
 use std::fmt::{Display, Formatter};
 use std::fs::File;
-use std::io::Read;
+use std::io::{BufRead, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
 use std::process::exit;
 
+mod ci;
+mod config;
+mod fix;
 mod git;
+mod message;
+mod report;
 
 #[tokio::main]
 async fn main() {
     let mut args = args_os();
     let cmd_name = args.next().expect("cmd name").into_string().unwrap();
-    match args.next().map(|x| x.into_string().unwrap()).as_deref() {
-        Some("--help" | "help") => print_help(cmd_name),
-        Some("edit") => {
-            let file = args.next().expect("no file specified");
-            let mut buffer = vec![];
-            File::open(file)
-                .expect("cannot open file")
-                .read_to_end(&mut buffer)
-                .expect("reading file");
-            let errors = check_commit_message(&buffer);
-            if !errors.is_empty() {
-                eprintln!("we found errors in commit message:");
-                print_errors(errors);
-                exit(1);
+    let mut options = GlobalOptions::default();
+    let command = loop {
+        match args.next().map(|x| x.into_string().unwrap()) {
+            Some(arg) if arg == "--no-config" => options.no_config = true,
+            Some(arg) if arg == "--strict" => options.strict = true,
+            Some(arg) if arg == "--quiet" || arg == "-q" => options.quiet = true,
+            Some(arg) if arg == "--config" => {
+                let path = args.next().unwrap_or_else(|| missing_value("--config"));
+                options.config = Some(PathBuf::from(path));
             }
+            command => break command,
+        }
+    };
+    match command.as_deref() {
+        Some("--help" | "help") => print_help(cmd_name),
+        Some("--version" | "version") => {
+            println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
         }
-        Some("check") => {
+        Some("edit") => edit(args, &options).await,
+        Some("check") => check(args, &options).await,
+        Some("types") => types(args, &options),
+        Some("parse") => parse(args),
+        Some("config-schema") => println!("{}", config::json_schema()),
+        Some("install") => {
+            let force = args.any(|x| x == "--force");
             let git = git::GitRepository::new_cwd();
-            let head_name = args
-                .next()
-                .expect("no head specified")
-                .into_string()
-                .unwrap();
-            let base_name = args
-                .next()
-                .expect("no base specified")
-                .into_string()
-                .unwrap();
-            let head = git
-                .rev_parse(&head_name)
-                .await
-                .expect("error calling git")
-                .expect("unknown head ref");
-            let base = git
-                .rev_parse(&base_name)
+            let hooks = git
+                .hooks_dir()
                 .await
                 .expect("error calling git")
-                .expect("unknown base ref");
-
-            let mut have_err = false;
-
-            for commit_hash in git
-                .get_commits(head, base)
-                .await
-                .expect("get commit list failed")
-            {
-                let commit = git
-                    .get_commit(commit_hash)
-                    .await
-                    .expect("getting commit")
-                    .expect("not found");
-                let errors = check_commit_message(&commit.message);
-                if !errors.is_empty() {
-                    eprintln!("we found errors in commit message of {commit_hash}");
-                    print_errors(errors);
-                    have_err = true;
-                }
+                .expect("not in a git repository");
+            std::fs::create_dir_all(&hooks).expect("creating hooks directory");
+            let hook = hooks.join("commit-msg");
+            if hook.exists() && !force {
+                eprintln!(
+                    "{} already exists. use --force to overwrite",
+                    hook.display()
+                );
+                exit(1);
             }
-            if have_err {
+            let exe = std::env::current_exe().expect("getting current executable");
+            let script = exe.to_str().map(hook_script).unwrap_or_else(|| {
+                eprintln!("{}: the path of executable is not UTF-8", exe.display());
                 exit(1);
+            });
+            std::fs::write(&hook, script).expect("writing hook");
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755))
+                    .expect("setting hook permission");
             }
+            eprintln!("installed commit-msg hook to {}", hook.display());
         }
         Some(cmd) => {
             eprintln!("invalid command: {cmd}");
@@ -83,33 +85,2233 @@ async fn main() {
     }
 }
 
-fn print_errors(errors: Vec<MessageError>) {
-    for x in errors {
-        eprintln!("  {}", x);
+/// the commit-msg hook script running `exe`
+fn hook_script(exe: &str) -> String {
+    // quote for the shell: `'` cannot be escaped in single quotes so close and reopen them
+    let exe = exe.replace('\'', "'\\''");
+    format!("#!/bin/sh\nexec '{exe}' edit \"$1\"\n")
+}
+
+#[test]
+fn hook_script_test() {
+    assert_eq!(
+        hook_script("/usr/bin/commitlint"),
+        "#!/bin/sh\nexec '/usr/bin/commitlint' edit \"$1\"\n"
+    );
+    assert_eq!(
+        hook_script("/home/it's me/$(x) `y`"),
+        "#!/bin/sh\nexec '/home/it'\\''s me/$(x) `y`' edit \"$1\"\n"
+    );
+}
+
+/// the options before the command
+#[derive(Default)]
+struct GlobalOptions {
+    /// use the built-in defaults without reading config files
+    no_config: bool,
+    /// the config file to use instead of the discovered one
+    config: Option<PathBuf>,
+    /// treat all warnings as errors
+    strict: bool,
+    /// print nothing in text format. the exit code still tells the result
+    quiet: bool,
+}
+
+impl GlobalOptions {
+    /// loads the config for the repository containing `dir`
+    fn load_config(&self, dir: &Path) -> Config {
+        let mut config = if self.no_config {
+            Config::default()
+        } else {
+            let config = match &self.config {
+                Some(path) => Config::load_file(path),
+                None => Config::load(dir),
+            };
+            config.unwrap_or_else(|e| {
+                eprintln!("{e}");
+                exit(1);
+            })
+        };
+        self.apply_strict(&mut config);
+        config
+    }
+
+    /// makes all errors in the config errors with `--strict`
+    fn apply_strict(&self, config: &mut Config) {
+        if self.strict {
+            for code in MessageError::CODES {
+                config.severity.insert(code.to_string(), Severity::Error);
+            }
+        }
+    }
+}
+
+#[test]
+fn strict_test() {
+    let options = GlobalOptions {
+        no_config: true,
+        strict: true,
+        ..GlobalOptions::default()
+    };
+    let config = options.load_config(Path::new("."));
+    let errors = [MessageError::InsufficientDescription];
+    assert!(has_error(&errors, &config));
+    assert!(!has_error(&errors, &Config::default()));
+}
+
+async fn edit(mut args: impl Iterator<Item = OsString>, options: &GlobalOptions) {
+    let config = options.load_config(Path::new("."));
+    let mut fix = false;
+    let mut interactive = false;
+    let mut warning_exit_code = 0;
+    let mut comment_char = None;
+    let mut format = report::OutputFormat::Text;
+    let mut file = None;
+    while let Some(arg) = args.next() {
+        match arg.to_str() {
+            Some("--fix") => fix = true,
+            Some("--interactive") => interactive = true,
+            Some("--comment-char") => {
+                let value = args.next().and_then(|x| x.into_string().ok());
+                let value = value.filter(|x| x == "auto" || x.chars().count() == 1);
+                comment_char = Some(value.unwrap_or_else(|| {
+                    eprintln!("--comment-char must be a character or 'auto'");
+                    exit(1);
+                }));
+            }
+            Some("--format") => {
+                let value = args.next().unwrap_or_else(|| missing_value("--format"));
+                let value = value.to_string_lossy();
+                format = match report::OutputFormat::parse(&value) {
+                    Some(
+                        format @ (report::OutputFormat::Text
+                        | report::OutputFormat::Json
+                        | report::OutputFormat::Github),
+                    ) => format,
+                    _ => {
+                        eprintln!(
+                            "unknown format: {value}. expected 'text', 'json', 'github', or 'auto'"
+                        );
+                        exit(1);
+                    }
+                };
+            }
+            Some("--warning-exit-code") => warning_exit_code = parse_exit_code(args.next()),
+            Some("--") => {
+                file = args.next();
+                break;
+            }
+            Some(option) if option.starts_with('-') && option != "-" => unknown_option(option),
+            _ => file = Some(arg),
+        }
+    }
+    let file = file.expect("no file specified");
+    let stdin = file == "-";
+    // the fixed message and the errors would be mixed in stdout
+    if stdin && (fix || interactive) && !matches!(format, report::OutputFormat::Text) {
+        eprintln!("--fix and --interactive with stdin requires --format text");
+        exit(1);
+    }
+    let mut buffer = vec![];
+    if stdin {
+        std::io::stdin()
+            .read_to_end(&mut buffer)
+            .expect("reading stdin");
+    } else {
+        File::open(&file)
+            .expect("cannot open file")
+            .read_to_end(&mut buffer)
+            .expect("reading file");
+    }
+    let comment_char = resolve_comment_char(comment_char, &config, options, &buffer).await;
+    // the diff after the scissors line of `git commit --verbose` is not a part of message
+    let scissors = std::str::from_utf8(&buffer)
+        .ok()
+        .and_then(|x| message::scissors_position(x, comment_char));
+    let diff = scissors.map_or_else(Vec::new, |x| buffer.split_off(x));
+    // git runs hooks with stdin from /dev/null so prompts use the terminal directly.
+    // fall back to checking only if there is no terminal
+    let terminal = if interactive {
+        std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/tty")
+            .ok()
+    } else {
+        None
+    };
+    if fix || terminal.is_some() {
+        if let Ok(message) = std::str::from_utf8(&buffer) {
+            let fixed = fix_before_comments(message, comment_char, |message| match &terminal {
+                Some(terminal) => fix_interactively(
+                    message,
+                    &config,
+                    comment_char,
+                    &mut std::io::BufReader::new(terminal),
+                    &mut &*terminal,
+                ),
+                None => fix::fix_message(message, &config, comment_char),
+            });
+            if fixed != message {
+                let mut contents = fixed.clone().into_bytes();
+                contents.extend_from_slice(&diff);
+                if !stdin {
+                    std::fs::write(&file, contents).expect("writing file");
+                }
+                buffer = fixed.into_bytes();
+            }
+        }
+        if stdin {
+            // there is no file to fix in place
+            print!("{}", String::from_utf8_lossy(&buffer));
+        }
+    }
+    let missing_newline = config.require_trailing_newline && !buffer.ends_with(b"\n");
+    if config.strip_comments {
+        if let Ok(message) = std::str::from_utf8(&buffer) {
+            buffer =
+                message::strip_comments(message, comment_char, config.strip_conflicts).into_bytes();
+        }
+    }
+    let mut errors = check_commit_message(&buffer, &config);
+    if missing_newline {
+        errors.push(MessageError::MissingTrailingNewline);
+    }
+    apply_profile(&config, &mut errors);
+    print_message_errors(&errors, &config, format, options.quiet);
+    exit_with_errors(&errors, &config, warning_exit_code);
+}
+
+/// applies `fix` to the message without the comments git adds after it, then appends them back.
+/// the comments would hide the footers and be wrapped into the body otherwise
+fn fix_before_comments(
+    message: &str,
+    comment_char: char,
+    fix: impl FnOnce(&str) -> String,
+) -> String {
+    let (message, comments) =
+        message.split_at(message::comment_tail_position(message, comment_char));
+    let mut fixed = fix(message);
+    fixed.push_str(comments);
+    fixed
+}
+
+#[test]
+fn fix_before_comments_test() {
+    let config = Config {
+        body_max_line_length: Some(30),
+        ..Config::default()
+    };
+    for comment_char in ['#', ';'] {
+        let message = "feat: add x\n\
+            \n\
+            short body\n\
+            \n\
+            Signed-off-by: Some Long Name <some.long.name@example.com>\n\
+            # Please enter the commit message for your changes. Lines starting\n\
+            # with '#' will be ignored, and an empty message aborts the commit.\n\
+            #\n\
+            # On branch main\n"
+            .replace('#', &comment_char.to_string());
+        let fixed = fix_before_comments(&message, comment_char, |x| {
+            fix::fix_message(x, &config, comment_char)
+        });
+        assert_eq!(fixed, message);
+    }
+    // the fixes still apply to the message
+    let fixed = fix_before_comments(
+        "feat: x\n\nthis is a long line to be wrapped by fix\n\n# comment\n",
+        '#',
+        |x| fix::fix_message(x, &config, '#'),
+    );
+    assert_eq!(
+        fixed,
+        "feat: x\n\nthis is a long line to be\nwrapped by fix\n\n# comment\n"
+    );
+}
+
+/// applies the fixes the user accepts one by one, asking on `output` and reading `input`
+fn fix_interactively(
+    message: &str,
+    config: &Config,
+    comment_char: char,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> String {
+    let mut message = message.to_string();
+    for (description, fix) in fix::fixes(config, comment_char) {
+        let fixed = fix(&message);
+        if fixed == message {
+            continue;
+        }
+        let _ = writeln!(output, "proposed fix: {description}");
+        for line in fixed.lines() {
+            let _ = writeln!(output, "  | {line}");
+        }
+        if confirm("apply this fix?", input, output) {
+            message = fixed;
+        }
+    }
+    message
+}
+
+/// asks a yes/no question. anything other than yes is no
+fn confirm(question: &str, input: &mut impl BufRead, output: &mut impl Write) -> bool {
+    let _ = write!(output, "{question} [y/N] ");
+    let _ = output.flush();
+    let mut answer = String::new();
+    if input.read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim(), "y" | "Y" | "yes")
+}
+
+#[test]
+fn fix_interactively_test() {
+    let config = Config {
+        forbid_multiple_blank_lines: true,
+        ..Config::default()
+    };
+    let message = "feat: x\n\n\nbody";
+    let run = |answers: &str| {
+        let mut output = Vec::new();
+        let fixed = fix_interactively(message, &config, '#', &mut answers.as_bytes(), &mut output);
+        (fixed, String::from_utf8(output).unwrap())
+    };
+
+    let (fixed, output) = run("y\ny\n");
+    assert_eq!(fixed, "feat: x\n\nbody\n");
+    assert!(output.starts_with("proposed fix: collapse consecutive blank lines\n"));
+    assert_eq!(output.matches("apply this fix? [y/N] ").count(), 2);
+
+    // the newline fix is still proposed after declining the first one
+    let (fixed, _) = run("n\nyes\n");
+    assert_eq!(fixed, "feat: x\n\n\nbody\n");
+    // no answer is no
+    let (fixed, _) = run("");
+    assert_eq!(fixed, message);
+}
+
+/// resolves the comment char from `--comment-char`, config, and `core.commentChar` in order.
+/// git config is not read with `--no-config`
+async fn resolve_comment_char(
+    option: Option<String>,
+    config: &Config,
+    options: &GlobalOptions,
+    message: &[u8],
+) -> char {
+    let value = match (option, config.comment_char) {
+        (Some(value), _) => value,
+        (None, Some(c)) => return c,
+        (None, None) if options.no_config => return '#',
+        (None, None) => {
+            let git = git::GitRepository::new_cwd();
+            let value = git.config_value("core.commentChar").await.ok().flatten();
+            value.unwrap_or_else(|| "#".to_string())
+        }
+    };
+    if value == "auto" {
+        std::str::from_utf8(message).map_or('#', message::auto_comment_char)
+    } else {
+        value.chars().next().unwrap_or('#')
+    }
+}
+
+async fn check(args: impl Iterator<Item = OsString>, options: &GlobalOptions) {
+    let mut repo = None;
+    let mut git_envs = Vec::new();
+    let mut unpushed = false;
+    let mut show_subject = false;
+    let mut count_only = false;
+    let mut include_base = false;
+    let mut merge_base = false;
+    let mut no_merges = false;
+    let mut first_parent = false;
+    let mut sections = false;
+    let mut type_casing_report = false;
+    let mut tags = None;
+    let mut fail_on_empty = false;
+    let mut before_tag = None;
+    let mut since_latest_tag = false;
+    let mut base_from_ci = false;
+    let mut batch_file = None;
+    let mut github_event = None;
+    let mut github_pr = false;
+    let mut monorepo = false;
+    let mut paths = vec![];
+    let mut squash_title = None;
+    let mut squash_body_file = None;
+    let mut jobs = std::thread::available_parallelism().map_or(1, |x| x.get());
+    let mut warning_exit_code = 0;
+    let mut format = report::OutputFormat::Text;
+    let mut output_path = None;
+    let mut output_only = false;
+    let quiet = options.quiet;
+    let mut positional = vec![];
+    let mut args = args.map(|x| x.into_string().unwrap());
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--git-env" => {
+                let env = args.next().unwrap_or_else(|| missing_value("--git-env"));
+                let (key, value) = env.split_once('=').unwrap_or_else(|| {
+                    eprintln!("invalid --git-env: {env}. expected KEY=VALUE");
+                    exit(1);
+                });
+                git_envs.push((key.to_string(), value.to_string()));
+            }
+            "-C" | "--repo" => {
+                repo = Some(PathBuf::from(
+                    args.next().unwrap_or_else(|| missing_value("--repo")),
+                ))
+            }
+            "--unpushed" => unpushed = true,
+            "--show-subject" => show_subject = true,
+            "--count-only" => count_only = true,
+            "--include-base" => include_base = true,
+            "--merge-base" => merge_base = true,
+            "--no-merges" => no_merges = true,
+            "--first-parent" => first_parent = true,
+            "--sections" => sections = true,
+            "--type-casing-report" => type_casing_report = true,
+            "--fail-on-empty" => fail_on_empty = true,
+            "--jobs" | "-j" => {
+                let value = args.next().unwrap_or_else(|| missing_value("--jobs"));
+                jobs = value.parse().unwrap_or_else(|_| {
+                    eprintln!("invalid --jobs: {value}. expected a number");
+                    exit(1);
+                });
+            }
+            "--base-from-ci" => base_from_ci = true,
+            "--monorepo" => monorepo = true,
+            "--path" => paths.push(args.next().unwrap_or_else(|| missing_value("--path"))),
+            "--squash-title" => {
+                squash_title = Some(
+                    args.next()
+                        .unwrap_or_else(|| missing_value("--squash-title")),
+                )
+            }
+            "--squash-body-file" => {
+                squash_body_file = Some(
+                    args.next()
+                        .unwrap_or_else(|| missing_value("--squash-body-file")),
+                )
+            }
+            "--github-event" => {
+                github_event = Some(
+                    args.next()
+                        .unwrap_or_else(|| missing_value("--github-event")),
+                )
+            }
+            "--github-pr" => github_pr = true,
+            "--batch-file" => {
+                batch_file = Some(args.next().unwrap_or_else(|| missing_value("--batch-file")))
+            }
+            "--before-tag" => {
+                before_tag = Some(args.next().unwrap_or_else(|| missing_value("--before-tag")))
+            }
+            "--since-latest-tag" => since_latest_tag = true,
+            "--tags" => tags = Some(args.next().unwrap_or_else(|| missing_value("--tags"))),
+            "--output" => {
+                output_path = Some(PathBuf::from(
+                    args.next().unwrap_or_else(|| missing_value("--output")),
+                ))
+            }
+            "--output-only" => output_only = true,
+            "--format" => {
+                let value = args.next().unwrap_or_else(|| missing_value("--format"));
+                format = report::OutputFormat::parse(&value).unwrap_or_else(|| {
+                    eprintln!(
+                        "unknown format: {value}. expected 'text', 'json', 'github', 'junit', 'sarif', or 'auto'"
+                    );
+                    exit(1);
+                });
+            }
+            "--warning-exit-code" => {
+                warning_exit_code = parse_exit_code(args.next().map(OsString::from))
+            }
+            "--" => {
+                positional.extend(&mut args);
+                break;
+            }
+            option if option.starts_with('-') => unknown_option(option),
+            _ => positional.push(arg),
+        }
+    }
+    // the refs are only used without the options choosing commits
+    let takes_refs = !(unpushed
+        || tags.is_some()
+        || before_tag.is_some()
+        || since_latest_tag
+        || base_from_ci
+        || batch_file.is_some()
+        || github_event.is_some()
+        || github_pr
+        || squash_title.is_some());
+    let max_positional = match positional.first() {
+        _ if !takes_refs => 0,
+        Some(range) if split_range(range).is_some() => 1,
+        _ => 2,
+    };
+    if let Some(arg) = positional.get(max_positional) {
+        unexpected_argument(arg);
+    }
+    let mut positional = positional.into_iter();
+    let repo = repo.unwrap_or_else(|| PathBuf::from("."));
+    if !repo.is_dir() {
+        eprintln!("{}: not a directory", repo.display());
+        exit(1);
+    }
+    let config = options.load_config(&repo);
+    if output_only && output_path.is_none() {
+        eprintln!("--output-only requires --output");
+        exit(1);
+    }
+
+    if squash_body_file.is_some() && squash_title.is_none() {
+        eprintln!("--squash-body-file requires --squash-title");
+        exit(1);
+    }
+    if let Some(title) = squash_title {
+        // the message is not a commit so the reports of commits are not available
+        let unsupported = [
+            (output_path.is_some(), "--output"),
+            (
+                matches!(format, report::OutputFormat::Junit),
+                "--format junit",
+            ),
+            (
+                matches!(format, report::OutputFormat::Sarif),
+                "--format sarif",
+            ),
+            (sections, "--sections"),
+            (count_only, "--count-only"),
+            (type_casing_report, "--type-casing-report"),
+        ];
+        if let Some((_, option)) = unsupported.iter().find(|(used, _)| *used) {
+            eprintln!("--squash-title cannot be used with {option}");
+            exit(1);
+        }
+        let body = squash_body_file.map_or_else(String::new, |path| {
+            std::fs::read_to_string(&path).unwrap_or_else(|e| {
+                eprintln!("{path}: {e}");
+                exit(1);
+            })
+        });
+        let message = squash_message(&title, &body);
+        let errors = check_commit_message(message.as_bytes(), &config);
+        print_message_errors(&errors, &config, format, quiet);
+        exit_with_errors(&errors, &config, warning_exit_code);
+        return;
+    }
+
+    let mut git = git::GitRepository::new(repo);
+    for (key, value) in git_envs {
+        git.add_env(key, value);
+    }
+    let packages = if monorepo {
+        let root = git.toplevel().await.expect("error calling git");
+        let root = root.unwrap_or_else(|| {
+            eprintln!("--monorepo requires a repository with working tree");
+            exit(1);
+        });
+        load_packages(&config, options, &root)
+    } else {
+        vec![]
+    };
+
+    let mut github_event = github_event.map(|path| {
+        ci::read_github_event(&path).unwrap_or_else(|e| {
+            eprintln!("{path}: {e}");
+            exit(1);
+        })
+    });
+    if let Some(ci::GithubEvent::Commits {
+        commits,
+        before: Some(before),
+    }) = &github_event
+    {
+        if !is_payload_complete(&git, commits, before).await {
+            github_event = Some(ci::GithubEvent::Range {
+                head: commits[0].0.clone(),
+                base: before.clone(),
+            });
+        }
+    }
+
+    let ranges = if let Some(event) = &github_event {
+        match event {
+            // checked without git below
+            ci::GithubEvent::Commits { .. } => vec![],
+            ci::GithubEvent::Range { head, base } => vec![CheckRange {
+                label: None,
+                head: rev_parse_commit(&git, head).await,
+                base: Some(rev_parse_commit(&git, base).await),
+            }],
+        }
+    } else if github_pr {
+        let Ok(path) = std::env::var("GITHUB_EVENT_PATH") else {
+            eprintln!("--github-pr requires GITHUB_EVENT_PATH");
+            exit(1);
+        };
+        let (head, base) = ci::read_pull_request(&path).unwrap_or_else(|e| {
+            eprintln!("{path}: {e}");
+            exit(1);
+        });
+        let detached = git.is_detached_head().await.expect("error calling git");
+        let head = if !detached {
+            rev_parse_commit(&git, "HEAD").await
+        } else if let Some(head) = git
+            .rev_parse(&format!("{head}^{{commit}}"))
+            .await
+            .expect("error calling git")
+        {
+            head
+        } else {
+            // actions/checkout checks out the merge commit GITHUB_SHA.
+            // its second parent is the real head of the pull request
+            let Ok(sha) = std::env::var("GITHUB_SHA") else {
+                eprintln!("unknown pull request head {head} and no GITHUB_SHA");
+                exit(1);
+            };
+            rev_parse_commit(&git, &format!("{sha}^2")).await
+        };
+        vec![CheckRange {
+            label: None,
+            head,
+            base: Some(rev_parse_commit(&git, &base).await),
+        }]
+    } else if let Some(path) = batch_file {
+        let entries = read_batch_file(&path).unwrap_or_else(|e| {
+            eprintln!("{path}: {e}");
+            exit(1);
+        });
+        let mut ranges = Vec::new();
+        for entry in entries {
+            let head_name = entry.head.as_deref().unwrap_or(&entry.branch);
+            let label = format!("{} ({}..{head_name})", entry.branch, entry.base);
+            ranges.push(CheckRange {
+                label: Some(label),
+                head: rev_parse_commit(&git, head_name).await,
+                base: Some(rev_parse_commit(&git, &entry.base).await),
+            });
+        }
+        ranges
+    } else if let Some(pattern) = tags {
+        let tags = git.list_tags(&pattern).await.expect("error calling git");
+        if tags.len() < 2 {
+            eprintln!(
+                "--tags {pattern} matched {} tags: at least two tags are required",
+                tags.len()
+            );
+            exit(1);
+        }
+        let mut ranges = Vec::new();
+        for pair in tags.windows(2) {
+            let (base_name, head_name) = (&pair[0], &pair[1]);
+            ranges.push(CheckRange {
+                label: Some(format!("tag {head_name} ({base_name}..{head_name})")),
+                head: rev_parse_commit(&git, head_name).await,
+                base: Some(rev_parse_commit(&git, base_name).await),
+            });
+        }
+        ranges
+    } else if since_latest_tag {
+        let tag = git.latest_tag().await.expect("error calling git");
+        let base = match &tag {
+            Some(tag) => Some(rev_parse_commit(&git, tag).await),
+            None => None,
+        };
+        if !quiet {
+            match &tag {
+                Some(tag) => eprintln!("checking commits since {tag}"),
+                None => eprintln!("no tags found: checking the whole history"),
+            }
+        }
+        vec![CheckRange {
+            label: None,
+            head: rev_parse_commit(&git, "HEAD").await,
+            base,
+        }]
+    } else if let Some(tag) = &before_tag {
+        vec![CheckRange {
+            label: None,
+            head: rev_parse_commit(&git, "HEAD").await,
+            base: Some(rev_parse_commit(&git, tag).await),
+        }]
+    } else if base_from_ci {
+        let Some((variable, branch)) = ci::base_branch(|name| std::env::var(name).ok()) else {
+            eprintln!("could not detect the base branch from CI environment variables");
+            eprintln!("checked: {}", ci::BASE_BRANCH_VARIABLES.join(", "));
+            exit(1);
+        };
+        let head = rev_parse_commit(&git, "HEAD").await;
+        // CI usually checks out only the remote tracking branch of the target
+        let mut target = None;
+        for name in [format!("origin/{branch}"), branch.clone()] {
+            target = git
+                .rev_parse(&format!("{name}^{{commit}}"))
+                .await
+                .expect("error calling git");
+            if target.is_some() {
+                break;
+            }
+        }
+        let Some(target) = target else {
+            eprintln!("base branch {branch} from {variable} is not found: fetch it before check");
+            exit(1);
+        };
+        let base = git
+            .merge_base(head, target)
+            .await
+            .expect("error calling git")
+            .unwrap_or_else(|| {
+                eprintln!(
+                    "no merge base with {branch}: fetch the history of {branch} before check"
+                );
+                exit(1);
+            });
+        vec![CheckRange {
+            label: None,
+            head,
+            base: Some(base),
+        }]
+    } else if unpushed {
+        let head = rev_parse_commit(&git, "HEAD").await;
+        let Some(base) = git
+            .rev_parse("@{upstream}")
+            .await
+            .expect("error calling git")
+        else {
+            if count_only {
+                println!("0");
+            } else {
+                eprintln!("no upstream configured for current branch: nothing to check");
+            }
+            return;
+        };
+        vec![CheckRange {
+            label: None,
+            head,
+            base: Some(base),
+        }]
+    } else {
+        let head_name = positional.next().unwrap_or_else(|| {
+            eprintln!("no head specified");
+            exit(1);
+        });
+        let (head_name, base_name) = match split_range(&head_name) {
+            Some((base, head)) => (head.to_string(), Some(base.to_string())),
+            None => (head_name, positional.next()),
+        };
+        let head = rev_parse_commit(&git, &head_name).await;
+        let base = match base_name {
+            Some(base_name) => rev_parse_commit(&git, &base_name).await,
+            None => {
+                // only the head commit: the range from and including itself
+                include_base = true;
+                head
+            }
+        };
+        vec![CheckRange {
+            label: None,
+            head,
+            base: Some(base),
+        }]
+    };
+
+    let has_payload = matches!(github_event, Some(ci::GithubEvent::Commits { .. }));
+    if fail_on_empty && ranges.is_empty() && !has_payload {
+        eprintln!("no commits to check: there are no ranges to check");
+        exit(1);
+    }
+    let mut results = Vec::new();
+    if let Some(ci::GithubEvent::Commits { commits, .. }) = github_event {
+        let commits = commits
+            .into_iter()
+            .map(|(id, message)| {
+                let hash = id.parse().unwrap_or_else(|_| {
+                    eprintln!("invalid commit id in the github event: {id}");
+                    exit(1);
+                });
+                (hash, git::CommitObject::from_message(message.into_bytes()))
+            })
+            .collect();
+        results.push((None, lint_messages(&config, commits)));
+    }
+    let log_options = git::LogOptions {
+        include_base,
+        no_merges,
+        first_parent,
+    };
+    for range in ranges {
+        let base = match range.base {
+            Some(base) if merge_base => {
+                let merge_base = git
+                    .merge_base(range.head, base)
+                    .await
+                    .expect("error calling git");
+                Some(merge_base.unwrap_or_else(|| {
+                    eprintln!("no merge base of {} and {base}", range.head);
+                    exit(1);
+                }))
+            }
+            base => base,
+        };
+        let commits = match base {
+            Some(base) => git.get_commits(range.head, base, log_options).await,
+            None => git.get_all_commits(range.head, log_options).await,
+        };
+        let commits = commits.expect("get commit list failed");
+        if fail_on_empty && commits.is_empty() {
+            match &range.label {
+                Some(label) => eprintln!("{label}: no commits to check"),
+                None => eprintln!("no commits to check: check the head and base refs"),
+            }
+            exit(1);
+        }
+        let commits = if paths.is_empty() {
+            commits
+        } else {
+            filter_by_paths(&git, commits, &paths).await
+        };
+        let progress =
+            !quiet && format == report::OutputFormat::Text && std::io::stderr().is_terminal();
+        let reports = lint_commits(&git, &config, commits, jobs, progress).await;
+        results.push((range.label, reports));
+    }
+    if !packages.is_empty() {
+        for (_, reports) in &mut results {
+            for report in reports {
+                let files = git
+                    .changed_files(report.hash)
+                    .await
+                    .expect("error calling git");
+                let errors = check_packages(&packages, &files, report);
+                report.errors.extend(errors);
+            }
+        }
+    }
+    let reports = results.iter().flat_map(|(_, x)| x).collect::<Vec<_>>();
+
+    if sections {
+        print!("{}", sections_text(&reports, &config));
+        return;
+    }
+
+    if type_casing_report {
+        print_type_casing_report(&reports);
+        return;
+    }
+
+    if count_only {
+        let failed = reports
+            .iter()
+            .filter(|x| has_error(&x.errors, &config))
+            .count();
+        println!("{failed}");
+        return;
+    }
+
+    if let Some(tag) = before_tag.as_ref().filter(|_| !quiet) {
+        eprintln!("{} new commits since {tag}", reports.len());
+    }
+
+    let output = match format {
+        report::OutputFormat::Text => report::text(&results, &config, show_subject),
+        report::OutputFormat::Junit => report::junit(&results, &config),
+        report::OutputFormat::Sarif => report::sarif(&results, &config),
+        report::OutputFormat::Json => report::json(&results, &config),
+        report::OutputFormat::Github => report::github(&results, &config),
+    };
+    if let Some(path) = &output_path {
+        if let Err(e) = std::fs::write(path, &output) {
+            eprintln!("writing {}: {e}", path.display());
+            exit(2);
+        }
+    }
+    if !output_only {
+        match format {
+            report::OutputFormat::Text if quiet => {}
+            report::OutputFormat::Text => eprint!("{output}"),
+            report::OutputFormat::Junit
+            | report::OutputFormat::Sarif
+            | report::OutputFormat::Json
+            | report::OutputFormat::Github => print!("{output}"),
+        }
+    }
+    let errors = results
+        .into_iter()
+        .flat_map(|(_, x)| x)
+        .flat_map(|x| x.errors)
+        .collect::<Vec<_>>();
+    exit_with_errors(&errors, &config, warning_exit_code);
+}
+
+/// prints the allowed types for commit pickers and other tools
+fn types(mut args: impl Iterator<Item = OsString>, options: &GlobalOptions) {
+    let config = options.load_config(Path::new("."));
+    let mut json = false;
+    while let Some(arg) = args.next() {
+        match arg.to_str() {
+            Some("--format") => match args.next().as_ref().and_then(|x| x.to_str()) {
+                Some("text") => json = false,
+                Some("json") => json = true,
+                _ => {
+                    eprintln!("--format must be 'text' or 'json'");
+                    exit(1);
+                }
+            },
+            Some(option) => unknown_option(option),
+            None => unknown_option(&arg.to_string_lossy()),
+        }
+    }
+
+    if json {
+        #[derive(serde::Serialize)]
+        struct TypeInfo<'a> {
+            #[serde(rename = "type")]
+            ty: &'a str,
+            description: Option<&'a str>,
+        }
+
+        let types = config
+            .types
+            .iter()
+            .map(|(ty, description)| TypeInfo {
+                ty,
+                description: description.as_deref(),
+            })
+            .collect::<Vec<_>>();
+        println!("{}", serde_json::to_string(&types).unwrap());
+    } else {
+        for (ty, description) in &config.types {
+            match description {
+                Some(description) => println!("{ty}: {description}"),
+                None => println!("{ty}"),
+            }
+        }
+    }
+}
+
+/// prints how the header is parsed without running any rules
+fn parse(mut args: impl Iterator<Item = OsString>) {
+    let mut json = false;
+    let mut file = None;
+    let mut message = None;
+    while let Some(arg) = args.next() {
+        match arg.to_str() {
+            Some("--format") => match args.next().as_ref().and_then(|x| x.to_str()) {
+                Some("text") => json = false,
+                Some("json") => json = true,
+                _ => {
+                    eprintln!("--format must be 'text' or 'json'");
+                    exit(1);
+                }
+            },
+            Some("--file") => file = Some(args.next().unwrap_or_else(|| missing_value("--file"))),
+            Some("--") => {
+                message = args.next();
+                break;
+            }
+            Some(option) if option.starts_with('-') && option != "-" => unknown_option(option),
+            _ => message = Some(arg),
+        }
+    }
+
+    let source = match (file, message) {
+        (Some(file), _) => std::fs::read_to_string(file).expect("reading file"),
+        (None, Some(message)) if message != "-" => message.into_string().expect("not utf8"),
+        (None, _) => {
+            let mut source = String::new();
+            std::io::stdin()
+                .read_to_string(&mut source)
+                .expect("reading stdin");
+            source
+        }
+    };
+    let message = message::Message::parse(&source);
+    let Some(header) = parse_header(message.header()) else {
+        eprintln!("{}", MessageError::HeaderNotFormatted);
+        exit(1);
+    };
+    let subject = header.subject.strip_prefix(' ').unwrap_or(header.subject);
+
+    if json {
+        #[derive(serde::Serialize)]
+        struct ParsedHeader<'a> {
+            #[serde(rename = "type")]
+            ty: &'a str,
+            multiple_types: bool,
+            scope: Option<&'a str>,
+            breaking: bool,
+            subject: &'a str,
+        }
+
+        let parsed = ParsedHeader {
+            ty: header.ty,
+            multiple_types: header.multiple_types,
+            scope: header.scope,
+            breaking: header.is_breaking,
+            subject,
+        };
+        println!("{}", serde_json::to_string(&parsed).unwrap());
+    } else {
+        println!("type: {}", header.ty);
+        if header.multiple_types {
+            println!("multiple types: true");
+        }
+        if let Some(scope) = header.scope {
+            println!("scope: {scope}");
+        }
+        println!("breaking: {}", header.is_breaking);
+        println!("subject: {subject}");
+    }
+}
+
+/// a range of commits to check
+struct CheckRange {
+    /// the label shown with the result if there are multiple ranges
+    label: Option<String>,
+    head: git::ObjectHash,
+    /// `None` to check the whole history
+    base: Option<git::ObjectHash>,
+}
+
+/// an entry of `check --batch-file`
+#[derive(Debug, Eq, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchEntry {
+    /// the label of the entry, also used as the head if `head` is omitted
+    branch: String,
+    base: String,
+    head: Option<String>,
+}
+
+/// reads the JSON array of `{ "branch", "base", "head" }` entries.
+/// YAML is not supported to avoid another dependency
+fn read_batch_file(path: &str) -> Result<Vec<BatchEntry>, String> {
+    let source = std::fs::read_to_string(path).map_err(|e| format!("reading batch file: {e}"))?;
+    parse_batch_file(&source)
+}
+
+fn parse_batch_file(source: &str) -> Result<Vec<BatchEntry>, String> {
+    let entries = serde_json::from_str::<Vec<BatchEntry>>(source)
+        .map_err(|e| format!("malformed batch file: {e}"))?;
+    for (i, entry) in entries.iter().enumerate() {
+        if entry.branch.is_empty() || entry.base.is_empty() || entry.head.as_deref() == Some("") {
+            return Err(format!("malformed batch file: entry {i} has empty ref"));
+        }
+    }
+    Ok(entries)
+}
+
+#[test]
+fn parse_batch_file_test() {
+    let entries = parse_batch_file(
+        r#"[
+            { "branch": "topic", "base": "main" },
+            { "branch": "queue/1", "base": "main", "head": "abc123" }
+        ]"#,
+    )
+    .unwrap();
+    assert_eq!(
+        entries,
+        vec![
+            BatchEntry {
+                branch: "topic".to_string(),
+                base: "main".to_string(),
+                head: None,
+            },
+            BatchEntry {
+                branch: "queue/1".to_string(),
+                base: "main".to_string(),
+                head: Some("abc123".to_string()),
+            },
+        ]
+    );
+    assert!(parse_batch_file(r#"[{ "branch": "topic" }]"#).is_err());
+    assert!(parse_batch_file(r#"[{ "branch": "topic", "base": "" }]"#).is_err());
+    assert!(parse_batch_file(r#"[{ "branch": "a", "base": "b", "typo": "c" }]"#).is_err());
+    assert!(parse_batch_file(r#"{ "branch": "a", "base": "b" }"#).is_err());
+}
+
+/// returns false if the oldest commit of push event payload is in the repository
+/// but does not have `before` as the parent, which means the payload is truncated.
+/// the commits are trusted if the oldest one is not fetched, like in shallow clones
+async fn is_payload_complete(
+    git: &git::GitRepository,
+    commits: &[(String, String)],
+    before: &str,
+) -> bool {
+    let Some((oldest, _)) = commits.last() else {
+        return false;
+    };
+    let (Ok(oldest), Ok(before)) = (oldest.parse(), before.parse::<git::ObjectHash>()) else {
+        return false;
+    };
+    match git.get_commit(oldest).await.expect("error calling git") {
+        Some(commit) => commit.parents().first() == Some(&before),
+        None => true,
+    }
+}
+
+#[tokio::test]
+async fn is_payload_complete_test() {
+    let Some(dir) = git::test_repo("payload", &["chore: init", "feat: a", "fix: b"]) else {
+        return;
+    };
+    let git = git::GitRepository::new(dir.clone());
+    let hash = |name: &str| {
+        let git = &git;
+        let name = name.to_string();
+        async move { git.rev_parse(&name).await.unwrap().unwrap().to_string() }
+    };
+    let (root, a, b) = (
+        hash("HEAD~2").await,
+        hash("HEAD~1").await,
+        hash("HEAD").await,
+    );
+    let commits = |ids: &[&String]| {
+        ids.iter()
+            .map(|x| (x.to_string(), String::new()))
+            .collect::<Vec<_>>()
+    };
+
+    assert!(is_payload_complete(&git, &commits(&[&b, &a]), &root).await);
+    // the oldest pushed commit is missing
+    assert!(!is_payload_complete(&git, &commits(&[&b]), &root).await);
+    // the commits are not fetched in the shallow clone
+    let unknown = "1111111111111111111111111111111111111111".to_string();
+    assert!(is_payload_complete(&git, &commits(&[&unknown]), &root).await);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// splits `BASE..HEAD` range into base and head. omitted side is `HEAD` like git.
+/// symmetric difference `A...B` is not a range to check
+fn split_range(range: &str) -> Option<(&str, &str)> {
+    if range.contains("...") {
+        return None;
+    }
+    let (base, head) = range.split_once("..")?;
+    Some((
+        if base.is_empty() { "HEAD" } else { base },
+        if head.is_empty() { "HEAD" } else { head },
+    ))
+}
+
+#[test]
+fn split_range_test() {
+    assert_eq!(
+        split_range("origin/main..HEAD"),
+        Some(("origin/main", "HEAD"))
+    );
+    assert_eq!(split_range("v1.0..topic"), Some(("v1.0", "topic")));
+    assert_eq!(split_range("origin/main.."), Some(("origin/main", "HEAD")));
+    assert_eq!(split_range("..topic"), Some(("HEAD", "topic")));
+    assert_eq!(split_range("HEAD~3"), None);
+    assert_eq!(split_range("main...topic"), None);
+}
+
+/// lints commits with at most `jobs` commits fetched concurrently.
+/// the reports are in the same order as `commits`.
+/// if `progress` is true, the number of checked commits is shown in a line of stderr
+async fn lint_commits(
+    git: &git::GitRepository,
+    config: &Config,
+    commits: Vec<git::ObjectHash>,
+    jobs: usize,
+    progress: bool,
+) -> Vec<CommitReport> {
+    let total = commits.len();
+    let mut checked = 0;
+    let mut reports = futures_util::stream::iter(commits)
+        .map(|commit_hash| async move {
+            let commit = git
+                .get_commit(commit_hash)
+                .await
+                .expect("getting commit")
+                .expect("not found");
+            if commit.is_merge() {
+                // merges may have custom messages unlike the templates check_commit_message skips
+                return CommitReport {
+                    hash: commit_hash,
+                    commit,
+                    errors: Vec::new(),
+                };
+            }
+            let mut errors = check_commit_message(&commit.message, config);
+            if config.revert_subject_match {
+                errors.extend(check_revert_subject(git, &commit).await);
+            }
+            if config.revert_breaking_hint {
+                errors.extend(check_revert_breaking(git, &commit).await);
+            }
+            if config.forbid_empty_changeset {
+                errors.extend(check_empty_changeset(git, commit_hash, &commit).await);
+            }
+            if let Some(max) = config.max_top_level_dirs {
+                errors.extend(check_commit_breadth(git, commit_hash, &commit, max).await);
+            }
+            apply_profile(config, &mut errors);
+            CommitReport {
+                hash: commit_hash,
+                commit,
+                errors,
+            }
+        })
+        .buffered(jobs.max(1))
+        .inspect(|_| {
+            if progress {
+                checked += 1;
+                eprint!("\rchecked {checked}/{total} commits");
+            }
+        })
+        .collect::<Vec<_>>()
+        .await;
+    if progress && total != 0 {
+        // clear the progress line for the report
+        eprint!("\r\x1b[K");
+    }
+
+    check_repeated_subjects(config, &mut reports);
+    reports
+}
+
+#[tokio::test]
+async fn lint_commits_fixture_test() {
+    let Some(dir) = git::test_repo(
+        "lint-fixture",
+        &["chore: init", "feat: good", "bad message"],
+    ) else {
+        return;
+    };
+
+    let git = git::GitRepository::new(dir.clone());
+    let head = git.rev_parse("HEAD").await.unwrap().unwrap();
+    let root = git.rev_parse("HEAD~2").await.unwrap().unwrap();
+    let commits = git
+        .get_commits(
+            head,
+            root,
+            git::LogOptions {
+                include_base: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let reports = lint_commits(&git, &Config::default(), commits, 1, false).await;
+    let errors = reports.into_iter().map(|x| x.errors).collect::<Vec<_>>();
+    assert_eq!(
+        errors,
+        [vec![MessageError::HeaderNotFormatted], vec![], vec![]]
+    );
+
+    // merges with custom messages are skipped by the parents
+    git::run_git(&dir, &["checkout", "-q", "-b", "topic"]);
+    git::run_git(
+        &dir,
+        &["commit", "-q", "--allow-empty", "-m", "feat: topic"],
+    );
+    git::run_git(&dir, &["checkout", "-q", "-"]);
+    git::run_git(&dir, &["commit", "-q", "--allow-empty", "-m", "fix: main"]);
+    git::run_git(
+        &dir,
+        &["merge", "-q", "--no-ff", "-m", "sync with topic", "topic"],
+    );
+    let merge = git.rev_parse("HEAD").await.unwrap().unwrap();
+    let commits = git
+        .get_commits(merge, head, git::LogOptions::default())
+        .await
+        .unwrap();
+    let reports = lint_commits(&git, &Config::default(), commits, 1, false).await;
+    assert_eq!(reports.len(), 3);
+    assert!(reports.iter().all(|x| x.errors.is_empty()));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// composes the message of squash merge like GitHub does from the pull request title and body
+fn squash_message(title: &str, body: &str) -> String {
+    let body = body.trim_matches(['\r', '\n']);
+    if body.is_empty() {
+        format!("{title}\n")
+    } else {
+        format!("{title}\n\n{body}\n")
+    }
+}
+
+#[test]
+fn squash_message_test() {
+    assert_eq!(squash_message("feat: x", ""), "feat: x\n");
+    assert_eq!(squash_message("feat: x", "\n"), "feat: x\n");
+    assert_eq!(
+        squash_message("feat: x (#1)", "* feat: a\n\n* fix: b\n"),
+        "feat: x (#1)\n\n* feat: a\n\n* fix: b\n"
+    );
+    let message = squash_message("Feat: x", "body");
+    assert_eq!(
+        check_commit_message(message.as_bytes(), &Config::default()),
+        vec![MessageError::HeaderTypeNotLower]
+    );
+}
+
+/// keeps the commits changing any file matching any of the globs
+async fn filter_by_paths(
+    git: &git::GitRepository,
+    commits: Vec<git::ObjectHash>,
+    globs: &[String],
+) -> Vec<git::ObjectHash> {
+    let mut result = Vec::new();
+    for commit in commits {
+        let files = git.changed_files(commit).await.expect("error calling git");
+        let matches = |file: &String| globs.iter().any(|glob| matches_glob(glob, file));
+        if files.iter().any(matches) {
+            result.push(commit);
+        }
+    }
+    result
+}
+
+/// matches the path against the glob. `*` and `?` do not match `/` but `**` does.
+/// the glob also matches files in the directory it matches, like `src` matches `src/main.rs`
+fn matches_glob(glob: &str, path: &str) -> bool {
+    fn matches(glob: &[u8], path: &[u8]) -> bool {
+        match glob {
+            [] => path.is_empty() || path[0] == b'/',
+            [b'*', b'*', rest @ ..] => {
+                let rest = rest.strip_prefix(b"/").unwrap_or(rest);
+                if rest.is_empty() {
+                    return true;
+                }
+                (0..=path.len())
+                    .any(|i| (i == 0 || path[i - 1] == b'/') && matches(rest, &path[i..]))
+            }
+            [b'*', rest @ ..] => {
+                let segment = path.iter().position(|&x| x == b'/').unwrap_or(path.len());
+                (0..=segment).any(|i| matches(rest, &path[i..]))
+            }
+            [b'?', rest @ ..] => {
+                path.first().is_some_and(|&x| x != b'/') && matches(rest, &path[1..])
+            }
+            [c, rest @ ..] => path.first() == Some(c) && matches(rest, &path[1..]),
+        }
+    }
+    matches(glob.trim_end_matches('/').as_bytes(), path.as_bytes())
+}
+
+#[test]
+fn matches_glob_test() {
+    assert!(matches_glob("src/main.rs", "src/main.rs"));
+    assert!(matches_glob("src", "src/main.rs"));
+    assert!(matches_glob("src/", "src/main.rs"));
+    assert!(!matches_glob("src", "src2/main.rs"));
+    assert!(matches_glob("*.rs", "main.rs"));
+    assert!(!matches_glob("*.rs", "src/main.rs"));
+    assert!(matches_glob("**/*.rs", "src/main.rs"));
+    assert!(matches_glob("**/*.rs", "main.rs"));
+    assert!(matches_glob("packages/*/src", "packages/a/src/lib.rs"));
+    assert!(!matches_glob("packages/*/src", "packages/a/b/src/lib.rs"));
+    assert!(matches_glob("packages/**/src", "packages/a/b/src/lib.rs"));
+    assert!(matches_glob("src/**", "src/a/b.rs"));
+    assert!(matches_glob("?.txt", "a.txt"));
+    assert!(!matches_glob("?.txt", "ab.txt"));
+}
+
+#[tokio::test]
+async fn filter_by_paths_test() {
+    let Some(dir) = git::test_repo("filter-by-paths", &[]) else {
+        return;
+    };
+    git::commit_files(&dir, &["packages/a/x.txt"], "feat: change a");
+    git::commit_files(&dir, &["packages/b/x.txt"], "feat: change b");
+    git::commit_files(&dir, &["README.md"], "docs: readme");
+
+    let git = git::GitRepository::new(dir.clone());
+    let head = git.rev_parse("HEAD").await.unwrap().unwrap();
+    let base = git.rev_parse("HEAD~2").await.unwrap().unwrap();
+    let commits = git
+        .get_commits(
+            head,
+            base,
+            git::LogOptions {
+                include_base: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let globs = |globs: &[&str]| globs.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+    let a = commits[2];
+    let b = commits[1];
+    let readme = commits[0];
+
+    assert_eq!(
+        filter_by_paths(&git, commits.clone(), &globs(&["packages/a"])).await,
+        vec![a]
+    );
+    assert_eq!(
+        filter_by_paths(&git, commits.clone(), &globs(&["packages/b", "*.md"])).await,
+        vec![readme, b]
+    );
+    assert!(filter_by_paths(&git, commits, &globs(&["src"]))
+        .await
+        .is_empty());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// a package of `check --monorepo`: the directory, the config path, and the config
+type Package = (String, String, Config);
+
+/// loads the configs in `package-configs` relative to the repository root
+fn load_packages(config: &Config, options: &GlobalOptions, root: &Path) -> Vec<Package> {
+    if config.package_configs.is_empty() {
+        eprintln!("--monorepo requires package-configs in config");
+        exit(1);
+    }
+    let mut packages = Vec::new();
+    for (dir, path) in &config.package_configs {
+        let mut package = Config::load_file(&root.join(path)).unwrap_or_else(|e| {
+            eprintln!("{}: {e}", path.display());
+            exit(1);
+        });
+        options.apply_strict(&mut package);
+        let dir = dir.trim_end_matches('/').to_string();
+        packages.push((dir, path.display().to_string(), package));
+    }
+    packages
+}
+
+/// checks the commit against the config of each package the commit changes files in.
+/// the errors are tagged with the config path and ones already in the report are skipped.
+/// the message and severity are resolved with the config of the package
+fn check_packages(
+    packages: &[Package],
+    files: &[String],
+    report: &CommitReport,
+) -> Vec<MessageError> {
+    let mut errors = Vec::new();
+    if report.commit.is_merge() {
+        // merges are skipped by the parents like lint_commits
+        return errors;
+    }
+    for (dir, path, config) in packages {
+        let in_package = |file: &&String| {
+            dir.is_empty()
+                || file
+                    .strip_prefix(dir.as_str())
+                    .is_some_and(|x| x.is_empty() || x.starts_with('/'))
+        };
+        if !files.iter().any(|x| in_package(&x)) {
+            continue;
+        }
+        for error in check_commit_message(&report.commit.message, config) {
+            if report.errors.contains(&error) {
+                continue;
+            }
+            errors.push(MessageError::FromConfig {
+                config: path.clone(),
+                severity: error.severity(config),
+                message: error.message(config),
+                error: Box::new(error),
+            });
+        }
+    }
+    errors
+}
+
+#[test]
+fn check_packages_test() {
+    let packages = vec![
+        (
+            "packages/a".to_string(),
+            "packages/a/commitlint.toml".to_string(),
+            Config {
+                scope_required_for: vec!["feat".to_string()],
+                ..Config::default()
+            },
+        ),
+        (
+            "packages/b".to_string(),
+            "packages/b/commitlint.toml".to_string(),
+            Config {
+                forbidden_words: vec!["wip".to_string()],
+                ..Config::default()
+            },
+        ),
+    ];
+    let report = CommitReport {
+        hash: "e2f8b41fc7543b5e3b30de7543ec851a86ddd57c".parse().unwrap(),
+        commit: git::CommitObject::from_message(b"Feat: wip".to_vec()),
+        errors: vec![MessageError::HeaderTypeNotLower],
+    };
+    let files = |files: &[&str]| files.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+    let from = |config: &str, error: MessageError| MessageError::FromConfig {
+        config: config.to_string(),
+        severity: Severity::Error,
+        message: error.to_string(),
+        error: Box::new(error),
+    };
+
+    assert_eq!(
+        check_packages(&packages, &files(&["README.md", "packages/ab/x"]), &report),
+        vec![]
+    );
+    assert_eq!(
+        check_packages(
+            &packages,
+            &files(&["packages/a/x", "packages/b/y"]),
+            &report
+        ),
+        vec![
+            from(
+                "packages/a/commitlint.toml",
+                MessageError::HeaderScopeRequired("feat".to_string())
+            ),
+            from(
+                "packages/b/commitlint.toml",
+                MessageError::ForbiddenWord("wip".to_string())
+            ),
+        ]
+    );
+
+    // merge with a custom message
+    let merge = CommitReport {
+        hash: report.hash,
+        commit: git::CommitObject::parse(
+            b"tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\
+            parent e2f8b41fc7543b5e3b30de7543ec851a86ddd57c\n\
+            parent 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\
+            \n\
+            sync wip with upstream\n",
+        )
+        .unwrap(),
+        errors: vec![],
+    };
+    assert_eq!(
+        check_packages(&packages, &files(&["packages/a/x", "packages/b/y"]), &merge),
+        vec![]
+    );
+
+    // message and severity come from the package config
+    let mut messages = BTreeMap::new();
+    messages.insert("ForbiddenWord".to_string(), "no wip here".to_string());
+    let mut severity = BTreeMap::new();
+    severity.insert("ForbiddenWord".to_string(), Severity::Warning);
+    let packages = vec![(
+        "packages/b".to_string(),
+        "packages/b/commitlint.toml".to_string(),
+        Config {
+            forbidden_words: vec!["wip".to_string()],
+            messages: config::Messages {
+                errors: messages,
+                ..Default::default()
+            },
+            severity,
+            ..Config::default()
+        },
+    )];
+    let errors = check_packages(&packages, &files(&["packages/b/y"]), &report);
+    let config = Config::default();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].message(&config),
+        "no wip here (from packages/b/commitlint.toml)"
+    );
+    assert_eq!(errors[0].severity(&config), Severity::Warning);
+}
+
+/// lints commits known only by message without git
+fn lint_messages(
+    config: &Config,
+    commits: Vec<(git::ObjectHash, git::CommitObject)>,
+) -> Vec<CommitReport> {
+    let mut reports = commits
+        .into_iter()
+        .map(|(hash, commit)| CommitReport {
+            hash,
+            errors: check_commit_message(&commit.message, config),
+            commit,
+        })
+        .collect::<Vec<_>>();
+    check_repeated_subjects(config, &mut reports);
+    reports
+}
+
+fn check_repeated_subjects(config: &Config, reports: &mut [CommitReport]) {
+    if config.profile == Profile::Minimal {
+        return;
+    }
+    if let Some(max) = config.max_repeated_subjects {
+        let mut repeated = 0;
+        for i in 0..reports.len() {
+            if i != 0 && reports[i - 1].commit.subject() == reports[i].commit.subject() {
+                repeated += 1;
+            } else {
+                repeated = 1;
+            }
+            if repeated > max {
+                let subject = String::from_utf8_lossy(reports[i].commit.subject()).into_owned();
+                reports[i]
+                    .errors
+                    .push(MessageError::RepeatedSubject(subject));
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn lint_commits_repeated_subject_test() {
+    let Some(dir) = git::test_repo(
+        "repeated-subject",
+        &[
+            "chore: init",
+            "fix: typo",
+            "fix: typo",
+            "fix: typo",
+            "feat: add x",
+            "fix: typo",
+        ],
+    ) else {
+        return;
+    };
+    let git = git::GitRepository::new(dir.clone());
+    let head = git.rev_parse("HEAD").await.unwrap().unwrap();
+    let commits = git
+        .get_all_commits(head, git::LogOptions::default())
+        .await
+        .unwrap();
+    let repeated = MessageError::RepeatedSubject("fix: typo".to_string());
+    for (max, expected) in [
+        // the third of consecutive three commits exceeds the limit
+        (2, [vec![], vec![], vec![], vec![], vec![repeated], vec![]]),
+        // three commits are within the limit
+        (3, Default::default()),
+    ] {
+        let config = Config {
+            max_repeated_subjects: Some(max),
+            ..Config::default()
+        };
+        let errors = lint_commits(&git, &config, commits.clone(), 1, false)
+            .await
+            .into_iter()
+            .map(|x| x.errors)
+            .collect::<Vec<_>>();
+        assert_eq!(errors, expected);
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn lint_commits_order_test() {
+    let messages = (0..16)
+        .map(|i| format!("feat: commit {i}"))
+        .collect::<Vec<_>>();
+    let messages = messages.iter().map(String::as_str).collect::<Vec<_>>();
+    let Some(dir) = git::test_repo("lint-order", &messages) else {
+        return;
+    };
+    let git = git::GitRepository::new(dir.clone());
+    let head = git.rev_parse("HEAD").await.unwrap().unwrap();
+    let base = git.rev_parse("HEAD~15").await.unwrap().unwrap();
+    let commits = git
+        .get_commits(
+            head,
+            base,
+            git::LogOptions {
+                include_base: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let config = Config::default();
+
+    let hashes = |reports: Vec<CommitReport>| reports.into_iter().map(|x| x.hash).collect();
+    let sequential: Vec<_> = hashes(lint_commits(&git, &config, commits.clone(), 1, false).await);
+    let parallel: Vec<_> = hashes(lint_commits(&git, &config, commits.clone(), 8, false).await);
+    assert_eq!(sequential, commits);
+    assert_eq!(parallel, commits);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// the number of passing commits for each changelog section, one line each
+fn sections_text(reports: &[&CommitReport], config: &Config) -> String {
+    let mut counts = Vec::<(&str, usize)>::new();
+    for section in config.sections.values() {
+        if !counts.iter().any(|(x, _)| x == section) {
+            counts.push((section, 0));
+        }
+    }
+
+    for report in reports.iter().filter(|x| !has_error(&x.errors, config)) {
+        let Ok(subject) = std::str::from_utf8(report.commit.subject()) else {
+            continue;
+        };
+        let Some(header) = parse_header(subject) else {
+            continue;
+        };
+        let Some(section) = config.sections.get(&header.ty.to_ascii_lowercase()) else {
+            continue;
+        };
+        if let Some((_, count)) = counts.iter_mut().find(|(x, _)| x == section) {
+            *count += 1;
+        }
+    }
+
+    counts
+        .into_iter()
+        .map(|(section, count)| format!("{section}: {count}\n"))
+        .collect()
+}
+
+#[test]
+fn sections_text_test() {
+    let mut sections = BTreeMap::new();
+    sections.insert("feat".to_string(), "Features".to_string());
+    sections.insert("fix".to_string(), "Bug Fixes".to_string());
+    sections.insert("perf".to_string(), "Bug Fixes".to_string());
+    sections.insert("docs".to_string(), "Documentation".to_string());
+    let config = Config {
+        sections,
+        ..Config::default()
+    };
+    let report = |message: &[u8], errors| CommitReport {
+        hash: "e2f8b41fc7543b5e3b30de7543ec851a86ddd57c".parse().unwrap(),
+        commit: git::CommitObject::from_message(message.to_vec()),
+        errors,
+    };
+    let reports = [
+        report(b"feat: add a", vec![]),
+        report(b"Fix: fix b", vec![]),
+        report(b"perf: speed up c", vec![]),
+        // failing commits and unknown types are not counted
+        report(b"feat: add d", vec![MessageError::HeaderSubjectEmpty]),
+        report(b"chore: bump", vec![]),
+        report(b"not formatted", vec![]),
+    ];
+    assert_eq!(
+        sections_text(&reports.iter().collect::<Vec<_>>(), &config),
+        "Documentation: 0\nFeatures: 1\nBug Fixes: 2\n"
+    );
+}
+
+/// prints the commits with non-lowercase type grouped by the type as written
+fn print_type_casing_report(reports: &[&CommitReport]) {
+    let mut groups = BTreeMap::<&str, Vec<git::ObjectHash>>::new();
+    for report in reports {
+        if !report.errors.contains(&MessageError::HeaderTypeNotLower) {
+            continue;
+        }
+        let Ok(subject) = std::str::from_utf8(report.commit.subject()) else {
+            continue;
+        };
+        if let Some(header) = parse_header(subject) {
+            groups.entry(header.ty).or_default().push(report.hash);
+        }
+    }
+
+    let count = groups.values().map(Vec::len).sum::<usize>();
+    println!("{count} commits with non-lowercase type");
+    for (ty, hashes) in groups {
+        println!("{ty}: {} commits", hashes.len());
+        for hash in hashes {
+            println!("  {hash}");
+        }
+    }
+}
+
+/// the result of linting one commit in check mode
+struct CommitReport {
+    hash: git::ObjectHash,
+    commit: git::CommitObject,
+    errors: Vec<MessageError>,
+}
+
+/// resolves the ref to a commit, peeling annotated tags
+async fn rev_parse_commit(git: &git::GitRepository, name: &str) -> git::ObjectHash {
+    git.rev_parse(&format!("{name}^{{commit}}"))
+        .await
+        .expect("error calling git")
+        .unwrap_or_else(|| {
+            eprintln!("unknown ref: {name}. fetch it if the repository is a shallow clone");
+            exit(1);
+        })
+}
+
+/// reports `feat` and `fix` commits without file changes
+/// unless they have `Allow-Empty` footer to tell it's intentional
+async fn check_empty_changeset(
+    git: &git::GitRepository,
+    hash: git::ObjectHash,
+    commit: &git::CommitObject,
+) -> Option<MessageError> {
+    let message = message::Message::parse(commit.message_str().ok()?);
+    let header = parse_header(message.header())?;
+    if !matches!(header.ty.to_ascii_lowercase().as_str(), "feat" | "fix") {
+        return None;
+    }
+    if message.footers.iter().any(|x| x.token == "Allow-Empty") {
+        return None;
+    }
+    let files = git.changed_files(hash).await.expect("error calling git");
+    files.is_empty().then_some(MessageError::EmptyChangeset)
+}
+
+#[tokio::test]
+async fn check_empty_changeset_test() {
+    let Some(dir) = git::test_repo("empty-changeset", &[]) else {
+        return;
+    };
+    git::commit_files(&dir, &["a.txt"], "feat: add a");
+    for message in [
+        "feat: nothing",
+        "chore: trigger ci",
+        "fix: nothing\n\nAllow-Empty: marks the release",
+    ] {
+        git::run_git(&dir, &["commit", "-q", "--allow-empty", "-m", message]);
+    }
+
+    let git = git::GitRepository::new(dir.clone());
+    let head = git.rev_parse("HEAD").await.unwrap().unwrap();
+    let base = git.rev_parse("HEAD~3").await.unwrap().unwrap();
+    let commits = git
+        .get_commits(
+            head,
+            base,
+            git::LogOptions {
+                include_base: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let config = Config {
+        forbid_empty_changeset: true,
+        ..Config::default()
+    };
+    let errors = lint_commits(&git, &config, commits, 1, false)
+        .await
+        .into_iter()
+        .map(|x| x.errors)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        errors,
+        vec![vec![], vec![], vec![MessageError::EmptyChangeset], vec![]]
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// warns commits changing files in more than `max` top-level directories
+/// since it may bundle unrelated changes. files in the root directory are not counted
+async fn check_commit_breadth(
+    git: &git::GitRepository,
+    hash: git::ObjectHash,
+    commit: &git::CommitObject,
+    max: usize,
+) -> Option<MessageError> {
+    if commit.is_merge() {
+        return None;
+    }
+    let files = git.changed_files(hash).await.expect("error calling git");
+    let dirs = files
+        .iter()
+        .filter_map(|x| x.split_once('/').map(|(dir, _)| dir))
+        .collect::<std::collections::BTreeSet<_>>()
+        .len();
+    (dirs > max).then_some(MessageError::CommitTooBroad { dirs })
+}
+
+#[tokio::test]
+async fn check_commit_breadth_test() {
+    let Some(dir) = git::test_repo("commit-breadth", &[]) else {
+        return;
+    };
+    git::commit_files(&dir, &["a/x", "b/x", "README.md"], "chore: init");
+    git::run_git(&dir, &["checkout", "-q", "-b", "topic"]);
+    git::commit_files(&dir, &["a/y", "b/y", "c/y"], "feat: broad");
+    git::run_git(&dir, &["checkout", "-q", "main"]);
+    git::commit_files(&dir, &["d/z"], "fix: focused");
+    git::run_git(
+        &dir,
+        &["merge", "-q", "--no-ff", "-m", "chore: merge", "topic"],
+    );
+
+    let git = git::GitRepository::new(dir.clone());
+    let head = git.rev_parse("HEAD").await.unwrap().unwrap();
+    let base = git.rev_parse("HEAD~1").await.unwrap().unwrap();
+    let root = git.rev_parse("HEAD~2").await.unwrap().unwrap();
+    let commits = git
+        .get_commits(
+            head,
+            root,
+            git::LogOptions {
+                include_base: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let config = Config {
+        max_top_level_dirs: Some(2),
+        ..Config::default()
+    };
+    let reports = lint_commits(&git, &config, commits, 1, false).await;
+    let errors = |hash| {
+        let report = reports.iter().find(|x| x.hash == hash).unwrap();
+        &report.errors
+    };
+    let broad = git.rev_parse("topic").await.unwrap().unwrap();
+    assert!(errors(head).is_empty());
+    assert!(errors(base).is_empty());
+    assert!(errors(root).is_empty());
+    assert_eq!(
+        errors(broad),
+        &vec![MessageError::CommitTooBroad { dirs: 3 }]
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// warns `revert` type commits reverting a `feat` commit without breaking change mark
+/// since removing a feature may break users of it
+async fn check_revert_breaking(
+    git: &git::GitRepository,
+    commit: &git::CommitObject,
+) -> Option<MessageError> {
+    let message = message::Message::parse(commit.message_str().ok()?);
+    let header = parse_header(message.header())?;
+    if !header.ty.eq_ignore_ascii_case("revert") || header.is_breaking {
+        return None;
+    }
+    let breaking_footer = message
+        .footers
+        .iter()
+        .any(|x| matches!(x.token, "BREAKING CHANGE" | "BREAKING-CHANGE"));
+    if breaking_footer {
+        return None;
+    }
+    let reverted = message::reverted_commit(message.message_lines())?;
+    let reverted = git.rev_parse(reverted).await.expect("error calling git")?;
+    let reverted = git.get_commit(reverted).await.expect("getting commit")?;
+    let reverted_header = parse_header(std::str::from_utf8(reverted.subject()).ok()?)?;
+    reverted_header
+        .ty
+        .eq_ignore_ascii_case("feat")
+        .then_some(MessageError::RevertBreakingHint)
+}
+
+#[tokio::test]
+async fn check_revert_breaking_test() {
+    let Some(dir) = git::test_repo("revert-breaking", &["feat: add api", "fix: fix crash"]) else {
+        return;
+    };
+    let git = git::GitRepository::new(dir.clone());
+    let feat = git.rev_parse("HEAD~1").await.unwrap().unwrap();
+    let fix = git.rev_parse("HEAD").await.unwrap().unwrap();
+
+    let check = |message: String| async {
+        let commit = git::CommitObject::from_message(message.into_bytes());
+        check_revert_breaking(&git, &commit).await
+    };
+    assert_eq!(
+        check(format!("revert: add api\n\nThis reverts commit {feat}.")).await,
+        Some(MessageError::RevertBreakingHint)
+    );
+    assert_eq!(
+        check(format!("revert!: add api\n\nThis reverts commit {feat}.")).await,
+        None
+    );
+    assert_eq!(
+        check(format!(
+            "revert: add api\n\nThis reverts commit {feat}.\n\nBREAKING CHANGE: api is removed"
+        ))
+        .await,
+        None
+    );
+    assert_eq!(
+        check(format!("revert: fix crash\n\nThis reverts commit {fix}.")).await,
+        None
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+async fn check_revert_subject(
+    git: &git::GitRepository,
+    commit: &git::CommitObject,
+) -> Option<MessageError> {
+    let message = message::Message::parse(commit.message_str().ok()?);
+    let reverted = message::reverted_commit(message.message_lines())?;
+    let reverted = git.rev_parse(reverted).await.expect("error calling git")?;
+    let reverted = git.get_commit(reverted).await.expect("getting commit")?;
+    let reverted_subject = std::str::from_utf8(reverted.subject()).ok()?;
+
+    if revert_header_matches(message.header(), reverted_subject) {
+        None
+    } else {
+        Some(MessageError::RevertSubjectMismatch)
+    }
+}
+
+/// returns true if the header is `Revert "<subject>"` like git makes,
+/// or `revert: <subject>` with the revert type
+fn revert_header_matches(header: &str, reverted_subject: &str) -> bool {
+    if reverted_subject.is_empty() {
+        return false;
+    }
+    if header == format!("Revert \"{reverted_subject}\"") {
+        return true;
+    }
+    parse_header(header).is_some_and(|x| {
+        x.ty.eq_ignore_ascii_case("revert") && x.subject.trim() == reverted_subject
+    })
+}
+
+#[test]
+fn revert_header_matches_test() {
+    assert!(revert_header_matches("Revert \"feat: a\"", "feat: a"));
+    assert!(revert_header_matches("revert: feat: a", "feat: a"));
+    assert!(revert_header_matches("revert(api)!: feat: a", "feat: a"));
+    assert!(!revert_header_matches(
+        "revert: Revert \"feat: a\" and more",
+        "feat: a"
+    ));
+    assert!(!revert_header_matches(
+        "Revert \"feat: a\" and more",
+        "feat: a"
+    ));
+    assert!(!revert_header_matches("revert: feat: ab", "feat: a"));
+    assert!(!revert_header_matches("fix: feat: a", "feat: a"));
+    assert!(!revert_header_matches("Revert \"\"", ""));
+}
+
+#[tokio::test]
+async fn check_revert_subject_test() {
+    let Some(dir) = git::test_repo("revert-subject", &["feat: add api"]) else {
+        return;
+    };
+    let git = git::GitRepository::new(dir.clone());
+    let feat = git.rev_parse("HEAD").await.unwrap().unwrap();
+
+    let check = |message: String| async {
+        let commit = git::CommitObject::from_message(message.into_bytes());
+        check_revert_subject(&git, &commit).await
+    };
+    assert_eq!(
+        check(format!(
+            "Revert \"feat: add api\"\n\nThis reverts commit {feat}."
+        ))
+        .await,
+        None
+    );
+    assert_eq!(
+        check(format!("revert: add api\n\nThis reverts commit {feat}.")).await,
+        Some(MessageError::RevertSubjectMismatch)
+    );
+    assert_eq!(
+        check(format!(
+            "revert: Revert \"feat: add api\" and more\n\nThis reverts commit {feat}."
+        ))
+        .await,
+        Some(MessageError::RevertSubjectMismatch)
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+fn has_error(errors: &[MessageError], config: &Config) -> bool {
+    errors.iter().any(|x| x.severity(config) == Severity::Error)
+}
+
+fn unknown_option(option: &str) -> ! {
+    eprintln!("unknown option: {option}");
+    eprintln!("use `--` before arguments starting with '-'");
+    exit(1);
+}
+
+fn missing_value(option: &str) -> ! {
+    eprintln!("no value for {option}");
+    exit(1);
+}
+
+fn unexpected_argument(arg: &str) -> ! {
+    eprintln!("unexpected argument: {arg}");
+    exit(1);
+}
+
+fn parse_exit_code(value: Option<OsString>) -> i32 {
+    let value = value.unwrap_or_else(|| missing_value("--warning-exit-code"));
+    let value = value.to_str().and_then(|x| x.parse().ok());
+    value.unwrap_or_else(|| {
+        eprintln!("invalid --warning-exit-code: expected a number");
+        exit(1);
+    })
+}
+
+/// exits with 1 if there are errors, or `warning_exit_code` if there are only warnings.
+/// returns if there are nothing to report
+fn exit_with_errors(errors: &[MessageError], config: &Config, warning_exit_code: i32) {
+    if has_error(errors, config) {
+        exit(1);
+    }
+    if !errors.is_empty() && warning_exit_code != 0 {
+        exit(warning_exit_code);
+    }
+}
+
+fn print_errors(errors: &[MessageError], config: &Config) {
+    eprint!("{}", report::errors_text(errors, config));
+}
+
+/// prints the errors of a message not in a commit in the format.
+/// nothing is printed in text format if `quiet` is true
+fn print_message_errors(
+    errors: &[MessageError],
+    config: &Config,
+    format: report::OutputFormat,
+    quiet: bool,
+) {
+    match format {
+        report::OutputFormat::Json => print!("{}", report::errors_json(errors, config)),
+        report::OutputFormat::Github => print!("{}", report::errors_github(errors, config)),
+        _ if !errors.is_empty() && !quiet => {
+            eprintln!("{}", config.messages.edit_banner());
+            print_errors(errors, config);
+        }
+        _ => {}
     }
 }
 
 fn print_help(cmd_name: String) {
-    eprintln!("Usage: {cmd_name} {{COMMAND}} [ARGUMENTS]");
+    eprintln!("Usage: {cmd_name} [OPTIONS] {{COMMAND}} [ARGUMENTS]");
     eprintln!("single binary commitlint only for conventional");
     eprintln!("version {}", env!("CARGO_PKG_VERSION"));
     eprintln!();
+    eprintln!("OPTIONS:");
+    eprintln!("\t--config PATH: use the config file at PATH");
+    eprintln!("\t--no-config: ignore config files and use the built-in defaults");
+    eprintln!("\t             this takes precedence over --config");
+    eprintln!("\t-q, --quiet: print no errors nor progress in text format. use the exit code");
+    eprintln!("\t--strict: treat all warnings as errors, even if severity config says warning");
+    eprintln!();
     eprintln!("COMMANDS:");
     eprintln!("\thelp|--help: Show this help message");
+    eprintln!("\tversion|--version: Show the version");
     eprintln!("\tedit: lint for commit-msg hook");
-    eprintln!("\t\tUsage: {cmd_name} edit {{file_path}}");
+    eprintln!(
+        "\t\tUsage: {cmd_name} edit [--fix|--interactive] [--comment-char C] [--] {{file_path}}"
+    );
+    eprintln!("\t\t--fix: fix the commit message in place if possible");
+    eprintln!("\t\t      if file_path is '-', the message is read from stdin and printed fixed");
+    eprintln!("\t\t      then only --format text is allowed");
+    eprintln!("\t\t--interactive: show each fix and ask whether to apply it on the terminal");
+    eprintln!("\t\t               checks only if there is no terminal to ask on");
+    eprintln!("\t\t--comment-char C: the character comment lines start with, or 'auto'");
+    eprintln!("\t\t                  defaults to comment-char config or core.commentChar of git");
+    eprintln!("\t\t--format FORMAT: 'text', or 'json' or 'github' to print errors to stdout");
+    eprintln!("\t\t                 'auto' is 'github' on GitHub Actions and 'text' otherwise");
+    eprintln!("\t\t                 defaults to 'text'");
+    eprintln!("\t\t--warning-exit-code N: exit with N if there are only warnings. defaults to 0");
     eprintln!("\tcheck: lint for ci");
-    eprintln!("\t\tUsage: {cmd_name} check {{HEAD_COMMIT}} {{BASE_COMMIT}}");
+    eprintln!("\t\tUsage: {cmd_name} check [OPTIONS] [--] {{HEAD_COMMIT}} {{BASE_COMMIT}}");
+    eprintln!("\t\t       {cmd_name} check [OPTIONS] [--] {{BASE_COMMIT}}..{{HEAD_COMMIT}}");
+    eprintln!("\t\t       {cmd_name} check [OPTIONS] [--] {{COMMIT}}");
+    eprintln!("\t\t       {cmd_name} check --unpushed");
+    eprintln!("\t\t       {cmd_name} check --tags {{PATTERN}}");
+    eprintln!("\t\t       {cmd_name} check --before-tag {{TAG}}");
+    eprintln!("\t\t       {cmd_name} check --since-latest-tag");
+    eprintln!("\t\t       {cmd_name} check --base-from-ci");
+    eprintln!("\t\t       {cmd_name} check --squash-title {{TITLE}} [--squash-body-file {{PATH}}]");
+    eprintln!("\t\t       {cmd_name} check --batch-file {{PATH}}");
+    eprintln!("\t\t       {cmd_name} check --github-event {{PATH}}");
+    eprintln!("\t\t--unpushed: check commits not pushed to the upstream of current branch");
+    eprintln!("\t\t--show-subject: show the subject of commits with errors");
+    eprintln!("\t\t--git-env KEY=VALUE: set environment variable for git commands");
+    eprintln!("\t\t-C, --repo PATH: check the repository at PATH instead of current directory");
+    eprintln!("\t\t--count-only: print only the number of failing commits and exit with 0");
+    eprintln!("\t\t--include-base: also check BASE_COMMIT. by default, BASE_COMMIT is excluded");
+    eprintln!("\t\t                like `git log BASE_COMMIT..HEAD_COMMIT`");
+    eprintln!("\t\t--merge-base: use the merge base of HEAD_COMMIT and BASE_COMMIT as BASE_COMMIT");
+    eprintln!("\t\t--no-merges: exclude merge commits from the commits to check");
+    eprintln!("\t\t--first-parent: follow only the first parent of merge commits");
+    eprintln!(
+        "\t\t                with --no-merges, merges on the first-parent chain are also excluded"
+    );
+    eprintln!("\t\t--warning-exit-code N: exit with N if there are only warnings. defaults to 0");
+    eprintln!("\t\t                      errors always exit with 1");
+    eprintln!("\t\t--sections: print the number of passing commits for each changelog section");
+    eprintln!("\t\t--type-casing-report: list commits with non-lowercase type and exit with 0");
+    eprintln!("\t\t--tags PATTERN: check commits between each consecutive tags matching PATTERN");
+    eprintln!("\t\t                fails if fewer than two tags match");
+    eprintln!("\t\t--fail-on-empty: fail if there are no commits to check");
+    eprintln!("\t\t--before-tag TAG: check commits reachable from HEAD but not from TAG");
+    eprintln!("\t\t--since-latest-tag: check commits since the latest tag reachable from HEAD");
+    eprintln!("\t\t                    or the whole history if there are no tags");
+    eprintln!(
+        "\t\t--base-from-ci: check commits since the merge base with the pull request target"
+    );
+    eprintln!("\t\t                branch detected from CI environment variables");
+    eprintln!("\t\t--github-event PATH: check the commits in GitHub Actions event payload");
+    eprintln!("\t\t                    like $GITHUB_EVENT_PATH");
+    eprintln!("\t\t                    uses git if the payload lacks messages or is truncated");
+    eprintln!(
+        "\t\t--github-pr: check the pull request of GitHub Actions event at $GITHUB_EVENT_PATH"
+    );
+    eprintln!("\t\t             on detached merge commit, the head of the pull request is used");
+    eprintln!("\t\t--batch-file PATH: check each range in the JSON array of");
+    eprintln!("\t\t                   {{\"branch\", \"base\", \"head\"}} entries. head defaults to branch");
+    eprintln!("\t\t                   the file must be JSON. YAML is not supported");
+    eprintln!("\t\t--squash-title TITLE: check the squash merge message composed of TITLE");
+    eprintln!("\t\t                      and --squash-body-file PATH instead of commits");
+    eprintln!("\t\t                      only text, json, and github formats are supported");
+    eprintln!("\t\t--path GLOB: check only commits changing files matching GLOB");
+    eprintln!("\t\t             can be specified multiple times to check commits matching any");
+    eprintln!("\t\t--monorepo: also check commits against package-configs of the directories");
+    eprintln!("\t\t            they change files in. errors are tagged with the config path");
+    eprintln!("\t\t-j, --jobs N: number of commits to check concurrently. defaults to CPU count");
+    eprintln!("\t\t             results are always shown in the order of git log");
+    eprintln!("\t\t--format FORMAT: 'text', or 'json', 'github', 'junit', or 'sarif' to print");
+    eprintln!("\t\t                 to stdout. defaults to 'text'");
+    eprintln!("\t\t                 'auto' is 'github' on GitHub Actions and 'text' otherwise");
+    eprintln!("\t\t--output PATH: also write the output in the format to PATH");
+    eprintln!("\t\t               exits with 2 if writing failed");
+    eprintln!("\t\t--output-only: write the output only to the --output file");
+    eprintln!("\ttypes: print the allowed types and their descriptions");
+    eprintln!("\t\tUsage: {cmd_name} types [--format text|json]");
+    eprintln!("\t\t--format FORMAT: 'text' (default) or 'json' for commit picker UIs");
+    eprintln!("\tparse: print how the header of the message is parsed without running rules");
+    eprintln!("\t\tUsage: {cmd_name} parse [--format text|json] {{MESSAGE}}");
+    eprintln!("\t\t       {cmd_name} parse [--format text|json] --file {{PATH}}");
+    eprintln!("\t\tthe message is read from stdin if MESSAGE is omitted or '-'");
+    eprintln!("\tconfig-schema: print JSON Schema of the config file for editor completion");
+    eprintln!("\tinstall: install commit-msg hook to current repository");
+    eprintln!("\t\tUsage: {cmd_name} install [--force]");
+    eprintln!("\t\t--force: overwrite existing commit-msg hook");
+    eprintln!();
+    eprintln!("CONFIG:");
+    eprintln!(
+        "\tconfig is loaded from {} in the root of the repository",
+        config::CONFIG_FILE_NAME
+    );
 }
 
 #[test]
 fn check_commit_message_test() {
     macro_rules! test {
-        ($message: literal$(, $err: ident $(( $($parm: expr),* $(,)? ))? )* $(,)?) => {
+        ($message: literal$(, $err: ident $(( $($parm: expr),* $(,)? ))? $({ $($field: ident: $value: expr),* $(,)? })? )* $(,)?) => {
+            test!(&Config::default() => $message$(, $err $(( $($parm),* ))? $({ $($field: $value),* })? )*)
+        };
+        ($config: expr => $message: literal$(, $err: ident $(( $($parm: expr),* $(,)? ))? $({ $($field: ident: $value: expr),* $(,)? })? )* $(,)?) => {
             assert_eq!(
-                check_commit_message($message),
+                check_commit_message($message, $config),
                 vec![
-                    $(MessageError::$err $(( $($parm),* ))? ),*
+                    $(MessageError::$err $(( $($parm),* ))? $({ $($field: $value),* })? ),*
                 ]
             );
         };
@@ -125,15 +2327,52 @@ fn check_commit_message_test() {
     test!(b"feat(not closed scope", HeaderNotFormatted);
     test!(b"feat:no space after colon", HeaderNoSpaceAfterColon);
     test!(b"FEAT: test", HeaderTypeNotLower);
+    test!(b"feat,fix: test", HeaderMultipleTypes);
+    test!(b"feat/fix(scope)!: test", HeaderMultipleTypes);
+    test!(b"feat, fix: test", HeaderMultipleTypes);
+    test!(b"feat,: test", HeaderNotFormatted);
+    test!(b"feat(a(b)): test", ScopeContainsParen);
+    test!(b"feat(a(b)!: test", ScopeContainsParen);
+    test!(b"feat(a(b): test", ScopeContainsParen);
     test!(b"tag: Test commit", HeaderUnknownType("tag".to_string()));
+    // \xE6\xA9\x9F\xE8\x83\xBD: "機能", \xC3\xA9: "é"
+    test!(b"\xE6\xA9\x9F\xE8\x83\xBD: x", HeaderTypeNonAscii);
+    test!(b"f\xC3\xA9at(scope): x", HeaderTypeNonAscii);
+    // no type prefix
+    test!(b"\xE6\x9B\xB4\xE6\x96\xB0", HeaderNotFormatted);
+    test!(b"\xE6\x9B\xB4\xE6\x96\xB0(x)", HeaderNotFormatted);
+    test!(b"feat: \xE6\xA9\x9F\xE8\x83\xBD");
+    test!(b"\xC3\xA9\xC3\xA9 message", HeaderNotFormatted);
     test!(b"fix: Not trimmed ", HeaderSubjectNotTrimmed);
     test!(b"fix:  Not trimmed", HeaderSubjectNotTrimmed);
+    // trailing whitespace is flagged regardless of line ending style
+    test!(b"fix: Not trimmed\t", HeaderSubjectNotTrimmed);
+    test!(b"fix: Not trimmed \n", HeaderSubjectNotTrimmed);
+    test!(b"fix: Not trimmed \r\n", HeaderSubjectNotTrimmed);
+    test!(
+        b"fix: Not trimmed\t\r\n\r\nbody\r\n",
+        HeaderSubjectNotTrimmed
+    );
+    test!(b"fix: Not trimmed\r", HeaderSubjectNotTrimmed);
+    test!(b"fix: Trimmed\r\n\r\nbody\r\n");
     // \xE3\x80\x80: U+3000
     test!(b"fix: \xE3\x80\x80Not trimmed", HeaderSubjectNotTrimmed);
+    // \xE2\x80\x8B: U+200B, \xEF\xBB\xBF: U+FEFF
+    test!(
+        b"fix: Zero\xE2\x80\x8Bwidth",
+        HeaderInvisibleCharacters(vec![10])
+    );
+    test!(
+        b"fix: Zero width\xEF\xBB\xBF\xE2\x80\x8B",
+        HeaderInvisibleCharacters(vec![16, 17]),
+    );
 
     test!(b"fix: I fixed some bug", HeaderSubjectMustNotASentence);
     test!(b"fix: We fixed some bug", HeaderSubjectMustNotASentence);
     test!(b"fix: You cannot use that", HeaderSubjectMustNotASentence);
+    test!(b"fix: Correct typo in README.md");
+    test!(b"fix: Correct typo in README, LICENSE, etc.");
+    test!(b"fix: Make sure we did it.", HeaderSubjectMustNotASentence);
     test!(b"fix: ", HeaderSubjectEmpty);
     test!(b"fix:", HeaderSubjectEmpty);
     test!(b"feat(scope)!: Test commit\nmessage", NoEmptyLineBeforeBody);
@@ -141,9 +2380,364 @@ fn check_commit_message_test() {
         b"feat(scope): Test commit\n\nBREAKING CHANGES: breaking",
         NoBangInBreakingChangeCommit,
     );
+    test!(
+        b"fix: Test commit\n\nbody\nRefs: #123",
+        NoEmptyLineBeforeFooter
+    );
+    test!(
+        b"fix: Test commit\n\nbody\nRefs: #123\nCloses #1\n",
+        NoEmptyLineBeforeFooter
+    );
+    test!(b"fix: Test commit\n\nbody\n\nRefs: #123\nCloses #1\n");
+    test!(b"fix: Test commit\n\nRefs: #123\n");
+    // colon-bearing body line that is not at the end
+    test!(b"fix: Test commit\n\nNote: this is\nprose in body\n");
+    test!(b"fix: Test commit\n\nbody with: colon\n");
+    // wrapped trailers directly after body are footers
+    test!(
+        b"fix: Test commit\n\nbody\nReviewed-by: Alice <alice@example.com>\n  and Bob\nRefs: #1\n",
+        NoEmptyLineBeforeFooter
+    );
+    // trailing prose with an indented line is not a footer unless it starts with a trailer
+    test!(b"fix: Test commit\n\nthis happens when:\n  the cache is cold\n");
+    test!(b"fix: Test commit\n\nRefs: #1\nbut more prose\n");
+    // multi-line breaking change footer
+    test!(b"feat!: Test commit\n\nbody\n\nBREAKING CHANGE: removed\n  the old api\nRefs: #1\n");
+    test!(
+        b"feat!: Test commit\n\nbody\nBREAKING CHANGE: removed\n  the old api\n",
+        NoEmptyLineBeforeFooter,
+    );
+    test!(
+        b"feat!: Test commit\n\nBREAKING CHANGE: removed\nthe old api\n",
+        BreakingChangeMalformed,
+    );
+    test!(
+        b"feat!: Test commit\n\nbody\n\nBREAKING CHANGE:\n",
+        BreakingChangeMalformed,
+    );
+
+    let config = Config {
+        forbidden_words: vec!["Codename".to_string(), "foo bar".to_string()],
+        ..Config::default()
+    };
+    test!(&config => b"feat: Add codename support", ForbiddenWord("Codename".to_string()));
+    test!(&config => b"feat(CODENAME): Add support", ForbiddenWord("Codename".to_string()));
+    test!(&config => b"feat: Add Foo  Bar support");
+    test!(&config => b"feat: Add foo bar support", ForbiddenWord("foo bar".to_string()));
+    test!(&config => b"feat: Add codenames support");
+    test!(&config => b"feat: Add support\n\nfor codename");
+    let config = Config {
+        forbidden_words_in_body: true,
+        ..config
+    };
+    test!(&config => b"feat: Add support\n\nfor codename", ForbiddenWord("Codename".to_string()));
+
+    let config = Config {
+        body_description_min_words: Some(3),
+        ..Config::default()
+    };
+    test!(&config => b"feat: Add support");
+    test!(&config => b"feat: x\n\n");
+    test!(&config => b"feat: x\n\n\n");
+    test!(&config => b"feat: Add support\n\nThis adds support.\n- item");
+    test!(&config => b"feat: Add support\n\n- item\n- item", BodyDescriptionMissing);
+    test!(&config => b"feat: Add support\n\nRefs: #1", BodyDescriptionMissing);
+    test!(&config => b"feat: Add support\n\nShort.\n\nLong enough paragraph", BodyDescriptionMissing);
+
+    let config = Config {
+        review_trailers: true,
+        ..Config::default()
+    };
+    test!(&config => b"feat: Add support\n\nReviewed-by: A <a@example.com>\nReviewed-by: B <b@example.com>\nTested-by: C <c@example.com>");
+    test!(
+        &config => b"feat: Add support\n\nAcked-by: A\nTested-by: <c@example.com>\nRefs: #1",
+        MalformedReviewTrailer { token: "Acked-by".to_string() },
+        MalformedReviewTrailer { token: "Tested-by".to_string() },
+    );
+
+    let config = Config {
+        revert_requires_reference: true,
+        ..Config::default()
+    };
+    test!(&config => b"revert: feat: x\n\nThis reverts commit e2f8b41fc7543b5e3b30de7543ec851a86ddd57c.");
+    test!(&config => b"revert: let us never again speak of the noodle incident\n\nRefs: 676104e, a215868");
+    test!(&config => b"revert: feat: x", RevertTypeMissingReference);
+    test!(&config => b"revert: feat: x\n\nbecause it's broken", RevertTypeMissingReference);
+    test!(&config => b"feat: x");
+
+    let config = Config {
+        forbid_multiple_blank_lines: true,
+        ..Config::default()
+    };
+    test!(&config => b"feat: x\n\nbody\n\nnext\n");
+    test!(&config => b"feat: x\n\nbody\n\n\nnext\n", MultipleConsecutiveBlankLines { line_number: 5 });
+    test!(
+        &config => b"feat: x\n\n\n\nbody\n\n\nnext\n",
+        MultipleConsecutiveBlankLines { line_number: 3 },
+        MultipleConsecutiveBlankLines { line_number: 7 },
+    );
+    test!(&config => b"feat: x\n\n```\n\n\n```\n");
+
+    let config = Config {
+        min_description_length: Some(20),
+        ..Config::default()
+    };
+    test!(&config => b"feat: x", InsufficientDescription);
+    test!(&config => b"feat: x\n\nRefs: #123456789012345678", InsufficientDescription);
+    test!(&config => b"feat: x\n\nThis adds x to support y");
+    test!(&config => b"feat: Add long enough subject");
+    test!(&config => b"chore: x");
+
+    let config = Config {
+        scope_policy: [
+            ("feat", ScopePolicy::Required),
+            ("docs", ScopePolicy::Optional),
+        ]
+        .into_iter()
+        .map(|(ty, policy)| (ty.to_string(), policy))
+        .collect(),
+        ..Config::default()
+    };
+    test!(&config => b"feat(parser): Add support");
+    test!(&config => b"feat: Add support", ScopeRequired("feat".to_string()));
+    test!(&config => b"Feat: Add support", HeaderTypeNotLower, ScopeRequired("feat".to_string()));
+    test!(&config => b"docs: Fix typo");
+    test!(&config => b"docs(readme): Fix typo");
+    test!(&config => b"fix: Fix crash");
+
+    let config = Config {
+        scope_policy: [("chore".to_string(), ScopePolicy::Forbidden)].into(),
+        ..Config::default()
+    };
+    test!(&config => b"chore: Update dependencies");
+    test!(&config => b"chore(deps): Update dependencies", ScopeForbidden("chore".to_string()));
+    test!(&config => b"chore(deps)!: Drop old runtime", ScopeForbidden("chore".to_string()));
+    test!(&config => b"feat(deps): Add support");
+
+    let config = Config {
+        header_max_length: Some(20),
+        ..Config::default()
+    };
+    test!(&config => b"feat: Add 20 letters");
+    test!(&config => b"feat: Add 21 letters!", HeaderTooLong(21));
+    // \xE3\x81\x82: U+3042, counted as one character
+    test!(&config => b"feat: \xE3\x81\x82\xE3\x81\x82\xE3\x81\x82\xE3\x81\x82\xE3\x81\x82\xE3\x81\x82\xE3\x81\x82\xE3\x81\x82\xE3\x81\x82\xE3\x81\x82\xE3\x81\x82\xE3\x81\x82\xE3\x81\x82\xE3\x81\x82");
+    test!(&config => b"Feat: This is too long", HeaderTooLong(22), HeaderTypeNotLower);
+
+    let config = Config {
+        subject_max_length: Some(10),
+        ..Config::default()
+    };
+    test!(&config => b"feat(very-long-scope): Add 10 ch");
+    test!(&config => b"feat: Add 11 char", HeaderSubjectTooLong(11));
+    test!(&config => b"feat: Add 10 ch ", HeaderSubjectNotTrimmed);
+
+    let config = Config {
+        subject_min_length: Some(3),
+        ..Config::default()
+    };
+    test!(&config => b"fix: typo");
+    test!(&config => b"fix: x", HeaderSubjectTooShort(1));
+    test!(&config => b"fix: ", HeaderSubjectEmpty);
+    test!(&config => b"fix:  ", HeaderSubjectNotTrimmed, HeaderSubjectEmpty);
+    test!(&config => b"fix: \t\xe3\x80\x80", HeaderSubjectNotTrimmed, HeaderSubjectEmpty);
+
+    let config = Config {
+        forbid_closing_keywords_in_body: true,
+        ..Config::default()
+    };
+    test!(&config => b"fix: x\n\nbody\n\nCloses #1\n");
+    test!(&config => b"fix: x\n\nThis fixes the crash\n");
+    test!(&config => b"fix: x\n\nbody\nthat resolves #1\n\nRefs: #2\n", ClosingKeywordInBody { line_number: 4 });
+
+    let config = Config {
+        body_max_line_length: Some(20),
+        ..Config::default()
+    };
+    test!(&config => b"feat: a header longer than twenty\n\nshort body\n");
+    test!(&config => b"feat: x\n\nthis body line is too long\nshort\n", BodyLineTooLong { line: 3, len: 26 });
+    test!(&config => b"feat: x\n\nshort\n\n- this list item is too long\n", BodyLineTooLong { line: 5, len: 28 });
+    test!(&config => b"feat: x\n\nsee\nhttps://example.com/very/long/url\n");
+    test!(&config => b"feat: x\n\nbody\n\nReviewed-by: Long Name <long@example.com>\n");
+    test!(&config => b"feat: x\nthis body line is too long\n", NoEmptyLineBeforeBody, BodyLineTooLong { line: 2, len: 26 });
+
+    let config = Config {
+        sentence_end_allowlist: vec![".md.".to_string()],
+        ..Config::default()
+    };
+    test!(&config => b"docs: Update README.md.");
+    test!(&config => b"docs: Update docs, etc.", HeaderSubjectMustNotASentence);
+
+    let config = Config {
+        require_perf_claim: true,
+        ..Config::default()
+    };
+    test!(&config => b"perf: Cache parsed config", PerfClaimMissing);
+    test!(&config => b"perf: Make parsing 2x faster");
+    test!(&config => b"perf: Cache parsed config\n\nThis reduces startup time by 30%.\n");
+    test!(&config => b"fix: Cache parsed config");
+
+    let config = Config {
+        forbid_weird_subject_casing: true,
+        acronyms: vec!["iOS".to_string()],
+        ..Config::default()
+    };
+    test!(&config => b"fix: fIx typo", HeaderSubjectWeirdCasing("fIx".to_string()));
+    test!(&config => b"feat: aDD button", HeaderSubjectWeirdCasing("aDD".to_string()));
+    test!(&config => b"feat: add button");
+    test!(&config => b"feat: Add button");
+    test!(&config => b"feat: API for users");
+    test!(&config => b"feat: iOS support");
+    test!(b"fix: fIx typo");
+
+    let config = Config {
+        forbid_weird_subject_casing: true,
+        acronyms: vec!["gRPC".to_string()],
+        ..Config::default()
+    };
+    test!(&config => b"feat: GitHub Actions workflow");
+    test!(&config => b"fix: macOS build");
+    test!(&config => b"feat: JavaScript bindings");
+    test!(&config => b"feat: gRPC server");
+    test!(&config => b"fix: GitHUB typo", HeaderSubjectWeirdCasing("GitHUB".to_string()));
+
+    let config = Config {
+        experimental_prefix: Some("x-".to_string()),
+        ..Config::default()
+    };
+    test!(&config => b"x-spike: try a new cache", ExperimentalType("x-spike".to_string()));
+    test!(&config => b"feat: add x");
+    test!(&config => b"spike: try a new cache", HeaderUnknownType("spike".to_string()));
+    test!(
+        b"x-spike: try a new cache",
+        HeaderUnknownType("x-spike".to_string())
+    );
+
+    let config = Config {
+        prefix_lowercase: true,
+        ..Config::default()
+    };
+    test!(&config => b"Feat(API): x", HeaderTypeNotLower, ScopeNotLower);
+    test!(&config => b"feat(api): x");
+    test!(&config => b"feat(api): Add X");
+    test!(b"feat(API): x");
+
+    let config = Config {
+        scope_required_for: vec!["feat".to_string(), "fix".to_string()],
+        ..Config::default()
+    };
+    test!(&config => b"feat(parser): Add support");
+    test!(&config => b"feat: Add support", HeaderScopeRequired("feat".to_string()));
+    test!(&config => b"feat!: Drop old runtime", HeaderScopeRequired("feat".to_string()));
+    test!(&config => b"fix: Fix crash", HeaderScopeRequired("fix".to_string()));
+    // scope-policy is still used for other types
+    let config = Config {
+        scope_policy: [("chore".to_string(), ScopePolicy::Required)].into(),
+        ..config
+    };
+    test!(&config => b"chore: Bump", ScopeRequired("chore".to_string()));
+    test!(&config => b"feat: Add support", HeaderScopeRequired("feat".to_string()));
+    let config = Config {
+        scope_required_for: vec!["feat".to_string(), "fix".to_string()],
+        ..Config::default()
+    };
+    test!(&config => b"chore: Update dependencies");
+
+    let config = Config {
+        scopes: Some(vec!["core".to_string(), "cli".to_string()]),
+        ..Config::default()
+    };
+    test!(&config => b"feat(core): Add support");
+    test!(&config => b"feat(core, cli): Add support");
+    test!(&config => b"feat: Add support");
+    test!(&config => b"feat(): Add support");
+    test!(&config => b"feat(web): Add support", HeaderUnknownScope("web".to_string()));
+    test!(&config => b"feat(Core): Add support", HeaderUnknownScope("Core".to_string()));
+    test!(&config => b"feat(cli,web): Add support", HeaderUnknownScope("web".to_string()));
+
+    let config = Config {
+        types: [("feat".to_string(), None), ("wip".to_string(), None)].into(),
+        ..Config::default()
+    };
+    test!(&config => b"wip: Try something");
+    test!(&config => b"fix: Fix crash", HeaderUnknownType("fix".to_string()));
+
+    let config: Config = toml::from_str(r#"types = ["feat", "fix", "deps"]"#).unwrap();
+    test!(&config => b"deps: bump x");
+    test!(&config => b"chore: bump x", HeaderUnknownType("chore".to_string()));
+
+    let config: Config = toml::from_str(r#"profile = "minimal""#).unwrap();
+    test!(&config => b"feat: Anything goes.");
+    test!(&config => b"feat(a(b)):  x \nno empty line\n\nBREAKING CHANGE: y");
+    test!(&config => b"feat:x", HeaderNoSpaceAfterColon);
+    test!(&config => b"wip: x", HeaderUnknownType("wip".to_string()));
+    test!(&config => b"Message Only", HeaderNotFormatted);
+
+    let config = Config {
+        forbid_subject_restating_scope: true,
+        ..Config::default()
+    };
+    test!(&config => b"feat(login): login", SubjectRestatesScope);
+    test!(&config => b"fix(parser): Parser: handle empty input", SubjectRestatesScope);
+    test!(&config => b"fix(parser): Handle empty input");
+    test!(&config => b"fix(parser): parsers are faster");
+    test!(&config => b"fix: parser");
+    test!(b"feat(login): login");
+
+    let config = Config {
+        forbid_url_in_subject: true,
+        ..Config::default()
+    };
+    test!(&config => b"fix: Follow https://example.com/spec", UrlInSubject);
+    test!(&config => b"fix: Fix protocol-relative // handling");
+    test!(&config => b"fix: Fix\n\nsee https://example.com/spec");
+    test!(b"fix: Follow https://example.com/spec");
+
+    let config = Config {
+        issue_reference_style: Some("hash".to_string()),
+        ..Config::default()
+    };
+    test!(&config => b"fix: Fix crash\n\nThis fixes #12.\n\nRefs: #12, #13");
+    test!(
+        &config => b"fix: Fix crash\n\nThis fixes GH-12.\n\nRefs: #12",
+        InconsistentIssueReference("GH-12".to_string()),
+    );
+    test!(b"fix: Fix crash\n\nThis fixes GH-12.\n\nRefs: #12");
+
+    let config = Config {
+        max_footers: Some(2),
+        ..Config::default()
+    };
+    test!(&config => b"fix: Fix crash\n\nRefs: #1\nReviewed-by: A <a@example.com>");
+    test!(
+        &config => b"fix: Fix crash\n\nRefs: #1\nRefs: #2\nCo-authored-by: B <b@example.com>\n  continued",
+        TooManyFooters { count: 3, max: 2 },
+    );
 }
 
-fn check_commit_message(title: &[u8]) -> Vec<MessageError> {
+fn check_commit_message(title: &[u8], config: &Config) -> Vec<MessageError> {
+    let mut errors = check_message_rules(title, config);
+    apply_profile(config, &mut errors);
+    errors
+}
+
+/// removes errors of rules disabled by `profile` config
+fn apply_profile(config: &Config, errors: &mut Vec<MessageError>) {
+    match config.profile {
+        Profile::Default => {}
+        Profile::Minimal => errors.retain(|x| {
+            matches!(
+                x,
+                MessageError::NotUtf8
+                    | MessageError::HeaderNotFormatted
+                    | MessageError::HeaderNoSpaceAfterColon
+                    | MessageError::HeaderUnknownType(_)
+            )
+        }),
+    }
+}
+
+fn check_message_rules(title: &[u8], config: &Config) -> Vec<MessageError> {
     let prefixes: &[&[u8]] = &[
         // merge: see fmt_merge_msg_title in fmt-merge-msg.c
         b"Merge branch ",
@@ -162,28 +2756,176 @@ fn check_commit_message(title: &[u8]) -> Vec<MessageError> {
     ];
     for prefix in prefixes {
         if title.starts_with(prefix) {
-            return vec![]
+            return vec![];
         }
     }
     let Ok(title) = std::str::from_utf8(title) else {
-        return vec![MessageError::NotUtf8]
+        return vec![MessageError::NotUtf8];
     };
 
     let mut errors = Vec::new();
 
-    let lines = title.lines().collect::<Vec<_>>();
+    let message = message::Message::parse(title);
+    let lines = &message.lines;
+
+    let is_breaking = check_header(message.header(), config, &mut errors);
+
+    check_forbidden_words(&message, config, &mut errors);
 
-    let is_breaking = check_header(lines[0], &mut errors);
+    if config.revert_requires_reference
+        && parse_header(message.header()).is_some_and(|x| x.ty.eq_ignore_ascii_case("revert"))
+    {
+        // the conventional commits spec recommends `Refs:` footer for reverts
+        let has_reference = message::reverted_commit(message.message_lines()).is_some()
+            || message.footers.iter().any(|x| x.token == "Refs");
+        if !has_reference {
+            errors.push(MessageError::RevertTypeMissingReference);
+        }
+    }
+
+    if let Some(min_length) = config.min_description_length {
+        if let Some(header) = parse_header(message.header()) {
+            let ty = header.ty.to_ascii_lowercase();
+            if config.min_description_types.contains(&ty) {
+                let meaningful = |x: &str| x.chars().filter(|x| !x.is_whitespace()).count();
+                let body = message.body_paragraphs().concat();
+                let length =
+                    meaningful(header.subject) + body.iter().map(|x| meaningful(x)).sum::<usize>();
+                if length < min_length {
+                    errors.push(MessageError::InsufficientDescription);
+                }
+            }
+        }
+    }
+
+    if config.require_perf_claim {
+        let is_perf =
+            parse_header(message.header()).is_some_and(|x| x.ty.eq_ignore_ascii_case("perf"));
+        let text = lines.join("\n");
+        if is_perf && !config.perf_claim_pattern.is_match(&text) {
+            errors.push(MessageError::PerfClaimMissing);
+        }
+    }
 
     if lines.len() == 1 {
         return errors;
     }
 
-    if lines[1] != "" {
+    if !lines[1].is_empty() {
         errors.push(MessageError::NoEmptyLineBeforeBody);
     }
 
-    let message_lines = &lines[2..];
+    if message.footer_follows_body() {
+        errors.push(MessageError::NoEmptyLineBeforeFooter);
+    }
+
+    // breaking change footer must be in the footer block and have a description
+    let body_end = message.footer_start.max(message.body_start);
+    let breaking_in_body = lines[message.body_start..body_end].iter().any(|x| {
+        let x = x.trim_start();
+        x.starts_with("BREAKING CHANGE:") || x.starts_with("BREAKING-CHANGE:")
+    });
+    let empty_breaking = message.footers.iter().any(|x| {
+        matches!(x.token, "BREAKING CHANGE" | "BREAKING-CHANGE") && x.value.trim().is_empty()
+    });
+    if breaking_in_body || empty_breaking {
+        errors.push(MessageError::BreakingChangeMalformed);
+    }
+
+    if config.forbid_multiple_blank_lines {
+        let mut last = None;
+        for line in message::extra_blank_lines(lines) {
+            if last != Some(line - 1) {
+                // report once for each run of blank lines
+                errors.push(MessageError::MultipleConsecutiveBlankLines {
+                    line_number: line + 1,
+                });
+            }
+            last = Some(line);
+        }
+    }
+
+    if config.review_trailers {
+        for footer in &message.footers {
+            if matches!(footer.token, "Reviewed-by" | "Acked-by" | "Tested-by")
+                && !message::is_name_and_email(&footer.value)
+            {
+                errors.push(MessageError::MalformedReviewTrailer {
+                    token: footer.token.to_string(),
+                });
+            }
+        }
+    }
+
+    if let Some(max) = config.body_max_line_length {
+        // the second line is also body if the blank line after the header is missing
+        let footer_start = message.footer_start.max(1);
+        for (line_index, line) in lines.iter().enumerate().take(footer_start).skip(1) {
+            // blank lines and single words like urls
+            if line.split_whitespace().nth(1).is_none() {
+                continue;
+            }
+            let len = line.chars().count();
+            if len > max {
+                errors.push(MessageError::BodyLineTooLong {
+                    line: line_index + 1,
+                    len,
+                });
+            }
+        }
+    }
+
+    if config.forbid_closing_keywords_in_body {
+        for (i, line) in lines
+            .iter()
+            .enumerate()
+            .take(body_end)
+            .skip(message.body_start)
+        {
+            if message::has_closing_keyword(line, &config.closing_keywords) {
+                errors.push(MessageError::ClosingKeywordInBody { line_number: i + 1 });
+            }
+        }
+    }
+
+    if let Some(max) = config.max_footers {
+        let count = message.footers.len();
+        if count > max {
+            errors.push(MessageError::TooManyFooters { count, max });
+        }
+    }
+
+    if let Some(style) = &config.issue_reference_style {
+        let body = message.message_lines().join("\n");
+        let references =
+            message::foreign_issue_references(&body, &config.issue_reference_patterns, style);
+        for reference in references {
+            errors.push(MessageError::InconsistentIssueReference(
+                reference.to_string(),
+            ));
+        }
+    }
+
+    // only checked if there is a body, which is not the case for blank lines after comments stripped
+    let has_body = !message.footers.is_empty()
+        || lines
+            .iter()
+            .skip(message.body_start)
+            .any(|x| !x.trim().is_empty());
+    if let Some(min_words) = config.body_description_min_words.filter(|_| has_body) {
+        let words = message.body_paragraphs().first().map_or(0, |paragraph| {
+            paragraph
+                .iter()
+                .take_while(|x| !message::is_list_item(x))
+                .map(|x| x.split_whitespace().count())
+                .sum()
+        });
+        if words < min_words {
+            errors.push(MessageError::BodyDescriptionMissing);
+        }
+    }
+
+    let message_lines = message.message_lines();
 
     fn is_breaking_footer(line: &str) -> bool {
         let trimmed = line.trim_start();
@@ -200,71 +2942,211 @@ fn check_commit_message(title: &[u8]) -> Vec<MessageError> {
     errors
 }
 
-fn check_header(line: &str, errors: &mut Vec<MessageError>) -> Option<bool> {
-    fn parse<'a>(line: &'a str) -> Option<(&'a str, Option<&'a str>, bool, &'a str)> {
-        let ty_end = line.find(|x| !matches!(x, 'a'..='z' | 'A'..='Z' | '0'..='9' | '-'))?;
-        let (ty, mut rest) = line.split_at(ty_end);
+fn check_forbidden_words(
+    message: &message::Message,
+    config: &Config,
+    errors: &mut Vec<MessageError>,
+) {
+    fn contains_word(text: &str, word: &str) -> bool {
+        let text = text.to_lowercase();
+        let word = word.to_lowercase();
+        text.match_indices(&word).any(|(i, _)| {
+            let before = text[..i].chars().next_back();
+            let after = text[i + word.len()..].chars().next();
+            !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+        })
+    }
 
-        let scope;
-        if rest.starts_with("(") {
-            rest = &rest[1..];
-            let close_scope = rest.find(')')?;
-            scope = Some(&rest[..close_scope]);
-            rest = &rest[close_scope + 1..];
-        } else {
-            scope = None;
-        }
-        let is_breaking;
-        if rest.starts_with("!") {
-            is_breaking = true;
-            rest = &rest[1..];
-        } else {
-            is_breaking = false;
+    for word in &config.forbidden_words {
+        let in_body = || {
+            config.forbidden_words_in_body
+                && message
+                    .message_lines()
+                    .iter()
+                    .any(|x| contains_word(x, word))
+        };
+        if contains_word(message.header(), word) || in_body() {
+            errors.push(MessageError::ForbiddenWord(word.clone()));
         }
+    }
+}
 
-        if !rest.starts_with(":") {
-            return None;
+/// the parsed header line: `type(scope)!: subject`
+#[derive(Debug)]
+struct Header<'a> {
+    ty: &'a str,
+    /// true if there are other types separated by `,` or `/` like `feat,fix: `
+    multiple_types: bool,
+    scope: Option<&'a str>,
+    is_breaking: bool,
+    subject: &'a str,
+}
+
+fn parse_header(line: &str) -> Option<Header<'_>> {
+    fn type_end(s: &str) -> Option<usize> {
+        s.find(|x| !matches!(x, 'a'..='z' | 'A'..='Z' | '0'..='9' | '-'))
+    }
+    let ty_end = type_end(line)?;
+    let (ty, mut rest) = line.split_at(ty_end);
+
+    let mut multiple_types = false;
+    while let Some(other) = rest.strip_prefix([',', '/']) {
+        let other = other.trim_start();
+        let other_end = type_end(other).filter(|&x| x != 0)?;
+        rest = &other[other_end..];
+        multiple_types = true;
+    }
+
+    let scope;
+    if rest.starts_with('(') {
+        rest = &rest[1..];
+        let mut close_scope = rest.find(')')?;
+        if rest[..close_scope].contains('(') {
+            // nested parentheses: take until the last `)` before `:` for better diagnostic
+            let colon = rest.find(':')?;
+            close_scope = rest[..colon].rfind(')')?;
         }
+        scope = Some(&rest[..close_scope]);
+        rest = &rest[close_scope + 1..];
+    } else {
+        scope = None;
+    }
+    let is_breaking;
+    if rest.starts_with('!') {
+        is_breaking = true;
         rest = &rest[1..];
+    } else {
+        is_breaking = false;
+    }
+
+    if !rest.starts_with(':') {
+        return None;
+    }
+    rest = &rest[1..];
+
+    Some(Header {
+        ty,
+        multiple_types,
+        scope,
+        is_breaking,
+        subject: rest,
+    })
+}
+
+fn check_header(line: &str, config: &Config, errors: &mut Vec<MessageError>) -> Option<bool> {
+    fn is_invisible(c: char) -> bool {
+        // zero width joiner is not included since it's used in emoji sequences
+        matches!(
+            c,
+            '\u{00AD}'
+                | '\u{180E}'
+                | '\u{200B}'
+                | '\u{200C}'
+                | '\u{200E}'
+                | '\u{200F}'
+                | '\u{202A}'..='\u{202E}'
+                | '\u{2060}'..='\u{2064}'
+                | '\u{2066}'..='\u{2069}'
+                | '\u{FEFF}'
+        )
+    }
 
-        Some((ty, scope, is_breaking, rest))
+    let invisible = line
+        .chars()
+        .enumerate()
+        .filter(|&(_, c)| is_invisible(c))
+        .map(|(i, _)| i + 1)
+        .collect::<Vec<_>>();
+    if !invisible.is_empty() {
+        errors.push(MessageError::HeaderInvisibleCharacters(invisible));
+    }
+    if let Some(max) = config.header_max_length {
+        let length = line.chars().count();
+        if length > max {
+            errors.push(MessageError::HeaderTooLong(length));
+        }
     }
 
-    let mut parsed = parse(line);
+    let mut parsed = parse_header(line);
 
-    if let Some((ty, _, _, ref mut subject)) = parsed {
+    if let Some(Header {
+        ty,
+        multiple_types,
+        scope,
+        ref mut subject,
+        ..
+    }) = parsed
+    {
+        if multiple_types {
+            errors.push(MessageError::HeaderMultipleTypes);
+        }
+        if scope.is_some_and(|x| x.contains('(')) {
+            errors.push(MessageError::ScopeContainsParen);
+        }
         let ty_lower = ty.to_ascii_lowercase();
         if ty_lower != ty {
             errors.push(MessageError::HeaderTypeNotLower);
         }
-        if !matches!(
-            ty_lower.as_str(),
-            "build"
-                | "chore"
-                | "ci"
-                | "docs"
-                | "feat"
-                | "fix"
-                | "perf"
-                | "refactor"
-                | "revert"
-                | "style"
-                | "test"
-        ) {
+        if config.prefix_lowercase && scope.is_some_and(|x| x.chars().any(char::is_uppercase)) {
+            errors.push(MessageError::ScopeNotLower);
+        }
+        if config.types.contains_key(&ty_lower) {
+            // known type
+        } else if config
+            .experimental_prefix
+            .as_ref()
+            .is_some_and(|x| ty_lower.starts_with(&x.to_ascii_lowercase()))
+        {
+            errors.push(MessageError::ExperimentalType(ty.to_string()));
+        } else {
             errors.push(MessageError::HeaderUnknownType(ty.to_string()));
         }
-        if *subject == "" {
+        if let (Some(scopes), Some(scope)) = (&config.scopes, scope) {
+            // multiple scopes like `feat(a,b): ` are checked one by one
+            let unknown = scope
+                .split(',')
+                .map(str::trim)
+                .filter(|x| !x.is_empty())
+                .filter(|x| !scopes.iter().any(|scope| scope == x));
+            for unknown in unknown {
+                errors.push(MessageError::HeaderUnknownScope(unknown.to_string()));
+            }
+        }
+        let scope_policy = config
+            .scope_policy
+            .get(&ty_lower)
+            .copied()
+            .unwrap_or_default();
+        if config.scope_required_for.contains(&ty_lower) {
+            // reported separately to configure the severity of scope-required-for
+            if scope.is_none() {
+                errors.push(MessageError::HeaderScopeRequired(ty_lower));
+            }
+        } else {
+            match scope_policy {
+                ScopePolicy::Required if scope.is_none() => {
+                    errors.push(MessageError::ScopeRequired(ty_lower));
+                }
+                ScopePolicy::Forbidden if scope.is_some() => {
+                    errors.push(MessageError::ScopeForbidden(ty_lower));
+                }
+                _ => {}
+            }
+        }
+        if subject.is_empty() {
             // fix
-        } else if subject.starts_with(" ") {
+        } else if subject.starts_with(' ') {
             *subject = &subject[1..];
         } else {
             errors.push(MessageError::HeaderNoSpaceAfterColon);
         }
+    } else if has_non_ascii_type(line) {
+        errors.push(MessageError::HeaderTypeNonAscii);
     } else {
         errors.push(MessageError::HeaderNotFormatted);
     }
 
-    let subject = parsed.map(|x| x.3).unwrap_or(line);
+    let subject = parsed.as_ref().map_or(line, |x| x.subject);
 
     let trimmed_subject = subject.trim();
     if trimmed_subject != subject {
@@ -272,8 +3154,12 @@ fn check_header(line: &str, errors: &mut Vec<MessageError>) -> Option<bool> {
     }
     let subject = trimmed_subject.to_ascii_lowercase();
     let mut like_a_sentence = false;
-    if subject.ends_with(".") {
-        like_a_sentence = true;
+    if subject.ends_with('.') {
+        let last_word = subject.split_whitespace().last().unwrap_or_default();
+        like_a_sentence = !config
+            .sentence_end_allowlist
+            .iter()
+            .any(|x| last_word.ends_with(&x.to_ascii_lowercase()));
     }
     if subject.starts_with("i ") | subject.starts_with("we ") | subject.starts_with("you ") {
         // disallow sentence with person as the subject
@@ -282,11 +3168,135 @@ fn check_header(line: &str, errors: &mut Vec<MessageError>) -> Option<bool> {
     if like_a_sentence {
         errors.push(MessageError::HeaderSubjectMustNotASentence);
     }
-    if subject == "" {
+    // whitespace-only subjects are empty rather than too short
+    if trimmed_subject.is_empty() {
         errors.push(MessageError::HeaderSubjectEmpty);
+    } else if let Some(min) = config.subject_min_length {
+        let length = trimmed_subject.chars().count();
+        if length < min {
+            errors.push(MessageError::HeaderSubjectTooShort(length));
+        }
+    }
+    if let Some(max) = config.subject_max_length {
+        let length = trimmed_subject.chars().count();
+        if length > max {
+            errors.push(MessageError::HeaderSubjectTooLong(length));
+        }
+    }
+    if config.forbid_weird_subject_casing {
+        let first_word = trimmed_subject
+            .split(|x: char| !x.is_alphabetic())
+            .next()
+            .unwrap_or_default();
+        if has_weird_casing(first_word)
+            && !KNOWN_MIXED_CASE_WORDS.contains(&first_word)
+            && !config.acronyms.iter().any(|x| x == first_word)
+        {
+            errors.push(MessageError::HeaderSubjectWeirdCasing(
+                first_word.to_string(),
+            ));
+        }
+    }
+    if config.forbid_url_in_subject && contains_url(&subject) {
+        errors.push(MessageError::UrlInSubject);
+    }
+    if config.forbid_subject_restating_scope {
+        let scope = parsed.as_ref().and_then(|x| x.scope);
+        let first_word = subject
+            .split_whitespace()
+            .next()
+            .map(|x| x.trim_end_matches(|x: char| x.is_ascii_punctuation()));
+        if scope.is_some_and(|scope| first_word == Some(&scope.trim().to_ascii_lowercase())) {
+            errors.push(MessageError::SubjectRestatesScope);
+        }
+    }
+
+    parsed.map(|x| x.is_breaking)
+}
+
+/// returns true if the part where type is expected is a word with non-ASCII letters like `機能: `.
+/// the header without colon is not formatted at all rather than with non-ASCII type
+fn has_non_ascii_type(line: &str) -> bool {
+    let Some(ty_end) = line.find(['(', '!', ':']) else {
+        return false;
+    };
+    if !line[ty_end..].contains(':') {
+        return false;
+    }
+    let ty = &line[..ty_end];
+    ty.contains(|x: char| !x.is_ascii() && x.is_alphabetic())
+        && ty.chars().all(|x| x.is_alphanumeric() || x == '-')
+}
+
+/// the well-known names allowed in `forbid-weird-subject-casing` in addition to `acronyms`
+const KNOWN_MIXED_CASE_WORDS: &[&str] = &[
+    "GitHub",
+    "GitLab",
+    "JavaScript",
+    "TypeScript",
+    "PowerShell",
+    "WebAssembly",
+    "WebSocket",
+    "OAuth",
+    "GraphQL",
+    "PostgreSQL",
+    "MySQL",
+    "SQLite",
+    "MongoDB",
+    "DynamoDB",
+    "YouTube",
+    "VSCode",
+    "iOS",
+    "iPadOS",
+    "macOS",
+    "tvOS",
+    "watchOS",
+    "iPhone",
+    "iPad",
+    "eBPF",
+];
+
+/// returns true if the word is none of lowercase, uppercase, capitalized,
+/// and uppercase with plural `s` like `URLs`
+fn has_weird_casing(word: &str) -> bool {
+    let word = word
+        .strip_suffix('s')
+        .filter(|x| x.len() > 1)
+        .unwrap_or(word);
+    let mut chars = word.chars();
+    let rest = chars.next().map_or("", |_| chars.as_str());
+    !(word.chars().all(char::is_lowercase)
+        || word.chars().all(char::is_uppercase)
+        || rest.chars().all(char::is_lowercase))
+}
+
+#[test]
+fn has_weird_casing_test() {
+    for word in ["add", "Add", "ADD", "URLs", "APIs", "s", "", "Über"] {
+        assert!(!has_weird_casing(word), "{word}");
     }
+    for word in ["fIx", "aDD", "ADd", "FIx", "iOS"] {
+        assert!(has_weird_casing(word), "{word}");
+    }
+}
+
+/// returns true if there is `http://` or `https://` starting a word
+fn contains_url(text: &str) -> bool {
+    text.match_indices("http").any(|(i, _)| {
+        let rest = &text[i + "http".len()..];
+        let starts_word = !text[..i].ends_with(|x: char| x.is_alphanumeric());
+        starts_word && (rest.starts_with("://") || rest.starts_with("s://"))
+    })
+}
 
-    parsed.map(|x| x.2)
+#[test]
+fn contains_url_test() {
+    assert!(contains_url("see https://example.com"));
+    assert!(contains_url("http://example.com"));
+    assert!(contains_url("link (https://example.com)"));
+    assert!(!contains_url("fix path // comment"));
+    assert!(!contains_url("support xhttp://"));
+    assert!(!contains_url("use https instead of http"));
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -294,25 +3304,256 @@ enum MessageError {
     NotUtf8,
 
     // about header line
+    HeaderInvisibleCharacters(Vec<usize>),
+    HeaderTooLong(usize),
     HeaderNotFormatted,
     HeaderNoSpaceAfterColon,
+    HeaderMultipleTypes,
     HeaderTypeNotLower,
+    HeaderTypeNonAscii,
     HeaderUnknownType(String),
+    ExperimentalType(String),
+    ScopeContainsParen,
+    ScopeNotLower,
+    HeaderUnknownScope(String),
+    ScopeRequired(String),
+    /// the type is in `scope-required-for` but has no scope
+    HeaderScopeRequired(String),
+    ScopeForbidden(String),
+    SubjectRestatesScope,
+    UrlInSubject,
     HeaderSubjectNotTrimmed,
     HeaderSubjectMustNotASentence,
     HeaderSubjectEmpty,
+    HeaderSubjectTooLong(usize),
+    HeaderSubjectTooShort(usize),
+    HeaderSubjectWeirdCasing(String),
     NoEmptyLineBeforeBody,
+    NoEmptyLineBeforeFooter,
+    BreakingChangeMalformed,
     NoBangInBreakingChangeCommit,
+    ForbiddenWord(String),
+    RevertSubjectMismatch,
+    BodyDescriptionMissing,
+    RepeatedSubject(String),
+    MalformedReviewTrailer {
+        token: String,
+    },
+    RevertTypeMissingReference,
+    MultipleConsecutiveBlankLines {
+        line_number: usize,
+    },
+    InsufficientDescription,
+    MissingTrailingNewline,
+    InconsistentIssueReference(String),
+    EmptyChangeset,
+    TooManyFooters {
+        count: usize,
+        max: usize,
+    },
+    ClosingKeywordInBody {
+        line_number: usize,
+    },
+    BodyLineTooLong {
+        line: usize,
+        len: usize,
+    },
+    CommitTooBroad {
+        dirs: usize,
+    },
+    PerfClaimMissing,
+    RevertBreakingHint,
+
+    // in check --monorepo
+    FromConfig {
+        config: String,
+        error: Box<MessageError>,
+        /// resolved with the config of the package
+        severity: Severity,
+        /// resolved with the config of the package
+        message: String,
+    },
+}
+
+#[test]
+fn codes_test() {
+    let mut codes = MessageError::CODES.to_vec();
+    codes.sort();
+    codes.dedup();
+    assert_eq!(codes.len(), MessageError::CODES.len());
+    for error in [
+        MessageError::NotUtf8,
+        MessageError::HeaderSubjectEmpty,
+        MessageError::CommitTooBroad { dirs: 3 },
+        MessageError::RevertBreakingHint,
+    ] {
+        assert!(MessageError::CODES.contains(&error.code()));
+    }
+}
+
+#[test]
+fn message_override_test() {
+    let mut config = Config::default();
+    config.messages.errors.insert(
+        "HeaderSubjectEmpty".to_string(),
+        "subject is required".to_string(),
+    );
+    assert_eq!(
+        MessageError::HeaderSubjectEmpty.message(&config),
+        "subject is required"
+    );
+    assert_eq!(
+        MessageError::HeaderNotFormatted.message(&config),
+        "commit first line is not formatted"
+    );
+}
+
+/// defines `MessageError::CODES` and `MessageError::code` from the list of variants
+/// so that they cannot get out of sync. `FromConfig` uses the code of the inner error
+macro_rules! error_codes {
+    ($($name:ident),* $(,)?) => {
+        /// all codes of errors in the order of declaration
+        const CODES: &'static [&'static str] = &[$(stringify!($name)),*];
+
+        /// the stable machine-readable name of the error
+        fn code(&self) -> &'static str {
+            match self {
+                MessageError::FromConfig { error, .. } => error.code(),
+                $(MessageError::$name { .. } => stringify!($name),)*
+            }
+        }
+    };
+}
+
+impl MessageError {
+    error_codes! {
+        NotUtf8,
+        HeaderInvisibleCharacters,
+        HeaderTooLong,
+        HeaderNotFormatted,
+        HeaderNoSpaceAfterColon,
+        HeaderMultipleTypes,
+        HeaderTypeNotLower,
+        HeaderTypeNonAscii,
+        HeaderUnknownType,
+        ExperimentalType,
+        ScopeContainsParen,
+        ScopeNotLower,
+        HeaderUnknownScope,
+        ScopeRequired,
+        HeaderScopeRequired,
+        ScopeForbidden,
+        SubjectRestatesScope,
+        UrlInSubject,
+        HeaderSubjectNotTrimmed,
+        HeaderSubjectMustNotASentence,
+        HeaderSubjectEmpty,
+        HeaderSubjectTooLong,
+        HeaderSubjectTooShort,
+        HeaderSubjectWeirdCasing,
+        NoEmptyLineBeforeBody,
+        NoEmptyLineBeforeFooter,
+        BreakingChangeMalformed,
+        NoBangInBreakingChangeCommit,
+        ForbiddenWord,
+        RevertSubjectMismatch,
+        BodyDescriptionMissing,
+        RepeatedSubject,
+        MalformedReviewTrailer,
+        RevertTypeMissingReference,
+        MultipleConsecutiveBlankLines,
+        InsufficientDescription,
+        MissingTrailingNewline,
+        InconsistentIssueReference,
+        EmptyChangeset,
+        TooManyFooters,
+        ClosingKeywordInBody,
+        BodyLineTooLong,
+        CommitTooBroad,
+        PerfClaimMissing,
+        RevertBreakingHint,
+    }
+
+    /// the message to show. this can be overridden with `messages.errors` config
+    fn message(&self, config: &Config) -> String {
+        if let MessageError::FromConfig {
+            config: path,
+            message,
+            ..
+        } = self
+        {
+            return format!("{message} (from {path})");
+        }
+        match config.messages.errors.get(self.code()) {
+            Some(message) => message.clone(),
+            None => self.to_string(),
+        }
+    }
+
+    /// the severity of the error. this can be overridden with `severity` config
+    fn severity(&self, config: &Config) -> Severity {
+        if let MessageError::FromConfig { severity, .. } = self {
+            return *severity;
+        }
+        if let Some(severity) = config.severity.get(self.code()) {
+            return *severity;
+        }
+        match self {
+            MessageError::RepeatedSubject(_) => Severity::Warning,
+            MessageError::InsufficientDescription => Severity::Warning,
+            MessageError::RevertBreakingHint => Severity::Warning,
+            MessageError::CommitTooBroad { .. } => Severity::Warning,
+            MessageError::ExperimentalType(_) => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
 }
 
 impl Display for MessageError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             MessageError::NotUtf8 => f.write_str("commit message is not utf8"),
+            MessageError::FromConfig { config, error, .. } => {
+                write!(f, "{error} (from {config})")
+            }
+            MessageError::HeaderInvisibleCharacters(columns) => {
+                let columns = columns.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+                write!(
+                    f,
+                    "commit first line contains invisible characters at column {}",
+                    columns.join(", ")
+                )
+            }
+            MessageError::HeaderTooLong(length) => {
+                write!(f, "commit first line is too long: {length} characters")
+            }
             MessageError::HeaderNotFormatted => f.write_str("commit first line is not formatted"),
             MessageError::HeaderNoSpaceAfterColon => f.write_str("no space after ':'"),
+            MessageError::HeaderMultipleTypes => f.write_str(
+                "commit has multiple types: split it into separate commits or choose one type",
+            ),
             MessageError::HeaderTypeNotLower => f.write_str("commit type is not lowercase"),
+            MessageError::HeaderTypeNonAscii => f.write_str(
+                "commit type contains non-ASCII characters: use an ASCII type like 'feat'",
+            ),
             MessageError::HeaderUnknownType(ty) => write!(f, "unknown header type: {}", ty),
+            MessageError::ExperimentalType(ty) => {
+                write!(f, "experimental type: {ty}. add it to types once it is settled")
+            }
+            MessageError::ScopeContainsParen => f.write_str("commit scope contains '('"),
+            MessageError::ScopeNotLower => f.write_str("commit scope is not lowercase"),
+            MessageError::HeaderUnknownScope(scope) => write!(f, "unknown scope: {scope}"),
+            MessageError::ScopeRequired(ty) => write!(f, "scope is required for {ty} commits"),
+            MessageError::HeaderScopeRequired(ty) => {
+                write!(f, "{ty} commits must have a scope (scope-required-for)")
+            }
+            MessageError::ScopeForbidden(ty) => write!(f, "scope is not allowed for {ty} commits"),
+            MessageError::SubjectRestatesScope => {
+                f.write_str("commit subject just restates the scope")
+            }
+            MessageError::UrlInSubject => {
+                f.write_str("commit subject contains URL: move the link to the body")
+            }
             MessageError::HeaderSubjectNotTrimmed => {
                 f.write_str("commit subject contains extra spaces")
             }
@@ -320,12 +3561,86 @@ impl Display for MessageError {
                 f.write_str("commit subject seems like a sentence")
             }
             MessageError::HeaderSubjectEmpty => f.write_str("commit subject is empty"),
+            MessageError::HeaderSubjectTooLong(length) => {
+                write!(f, "subject is too long: {length} characters")
+            }
+            MessageError::HeaderSubjectTooShort(length) => {
+                write!(f, "subject is too short: {length} characters")
+            }
+            MessageError::HeaderSubjectWeirdCasing(word) => {
+                write!(f, "first word of subject has unusual casing: {word}")
+            }
             MessageError::NoEmptyLineBeforeBody => {
                 f.write_str("there is no empty line before body")
             }
+            MessageError::NoEmptyLineBeforeFooter => {
+                f.write_str("there is no empty line before footer")
+            }
+            MessageError::BreakingChangeMalformed => f.write_str(
+                "BREAKING CHANGE footer must be in the footer block and have a description",
+            ),
             MessageError::NoBangInBreakingChangeCommit => {
                 f.write_str("no '!' in first line in breaking change commit")
             }
+            MessageError::ForbiddenWord(word) => {
+                write!(f, "commit message contains forbidden word: {}", word)
+            }
+            MessageError::RevertSubjectMismatch => {
+                f.write_str("revert subject does not match the subject of reverted commit")
+            }
+            MessageError::BodyDescriptionMissing => {
+                f.write_str("body does not start with a description")
+            }
+            MessageError::MalformedReviewTrailer { token } => {
+                write!(f, "{token} footer is not in 'Name <email>' format")
+            }
+            MessageError::RevertTypeMissingReference => {
+                f.write_str("revert commit does not reference the reverted commit in body")
+            }
+            MessageError::MultipleConsecutiveBlankLines { line_number } => {
+                write!(f, "multiple consecutive blank lines at line {line_number}")
+            }
+            MessageError::InsufficientDescription => {
+                f.write_str("commit message seems too short to describe the change")
+            }
+            MessageError::MissingTrailingNewline => {
+                f.write_str("commit message file does not end with a newline")
+            }
+            MessageError::InconsistentIssueReference(reference) => {
+                write!(
+                    f,
+                    "issue reference {reference} is not in the configured style"
+                )
+            }
+            MessageError::EmptyChangeset => f.write_str(
+                "feat or fix commit has no file changes: add Allow-Empty footer if intended",
+            ),
+            MessageError::TooManyFooters { count, max } => {
+                write!(
+                    f,
+                    "too many footers: {count} footers but at most {max} allowed"
+                )
+            }
+            MessageError::ClosingKeywordInBody { line_number } => write!(
+                f,
+                "issue closing keyword in body at line {line_number}: move it to footer"
+            ),
+            MessageError::BodyLineTooLong { line, len } => {
+                write!(f, "body line {line} is too long: {len} characters")
+            }
+            MessageError::CommitTooBroad { dirs } => write!(
+                f,
+                "commit changes files in {dirs} top-level directories: consider splitting it"
+            ),
+            MessageError::PerfClaimMissing => {
+                f.write_str("perf commit does not quantify the improvement like '30% faster'")
+            }
+            MessageError::RevertBreakingHint => f.write_str(
+                "reverting a feature may break users: consider adding '!' or BREAKING CHANGE footer",
+            ),
+            MessageError::RepeatedSubject(subject) => {
+                write!(f, "same subject is repeated consecutively: {}", subject)
+            }
         }
     }
 }
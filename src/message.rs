@@ -0,0 +1,603 @@
+use regex::Regex;
+use std::collections::BTreeMap;
+
+/// The commit message split into lines with the footer block detected.
+#[derive(Debug)]
+pub(crate) struct Message<'a> {
+    /// all lines of the message. the first line is the header
+    pub lines: Vec<&'a str>,
+    /// the index of the first line of body. this is 2 if there is empty line before body
+    pub body_start: usize,
+    /// the index of the first line of the footer block.
+    /// if there are no footers, this is the index of the line after the last non-empty line
+    pub footer_start: usize,
+    pub footers: Vec<Footer<'a>>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) struct Footer<'a> {
+    pub token: &'a str,
+    /// the value of the footer. indented continuation lines are joined with `\n`
+    pub value: String,
+    /// the index of the line in `Message::lines`
+    pub line: usize,
+}
+
+impl<'a> Message<'a> {
+    pub fn parse(message: &'a str) -> Self {
+        let mut lines = message.lines().collect::<Vec<_>>();
+        if lines.is_empty() {
+            lines.push("");
+        }
+
+        let body_start = if lines.get(1).is_some_and(|x| x.is_empty()) {
+            2
+        } else {
+            1
+        };
+
+        let mut footer_end = lines.len();
+        while footer_end > body_start && lines[footer_end - 1].trim().is_empty() {
+            footer_end -= 1;
+        }
+
+        // footers are the run of trailers at the very end of the message.
+        // indented lines are continuation of the previous trailer
+        let mut footer_start = footer_end;
+        let mut i = footer_end;
+        while i > body_start {
+            let line = lines[i - 1];
+            if parse_trailer(line).is_some() {
+                footer_start = i - 1;
+            } else if !is_continuation(line) {
+                break;
+            }
+            i -= 1;
+        }
+
+        let mut footers = Vec::<Footer>::new();
+        for (line, &content) in lines.iter().enumerate().take(footer_end).skip(footer_start) {
+            if let Some((token, value)) = parse_trailer(content) {
+                let value = value.to_string();
+                footers.push(Footer { token, value, line });
+            } else {
+                let footer = footers.last_mut().expect("starts with trailer");
+                footer.value.push('\n');
+                footer.value.push_str(content.trim());
+            }
+        }
+
+        Self {
+            lines,
+            body_start,
+            footer_start,
+            footers,
+        }
+    }
+
+    pub fn header(&self) -> &'a str {
+        self.lines[0]
+    }
+
+    /// lines after the header line
+    pub fn message_lines(&self) -> &[&'a str] {
+        self.lines.get(2..).unwrap_or_default()
+    }
+
+    /// paragraphs of the body, not including footers
+    pub fn body_paragraphs(&self) -> Vec<&[&'a str]> {
+        let body_end = self.footer_start.max(self.body_start);
+        self.lines[self.body_start..body_end]
+            .split(|x| x.trim().is_empty())
+            .filter(|x| !x.is_empty())
+            .collect()
+    }
+
+    /// returns true if the footer block directly follows a body line
+    pub fn footer_follows_body(&self) -> bool {
+        !self.footers.is_empty()
+            && self.footer_start > self.body_start
+            && !self.lines[self.footer_start - 1].trim().is_empty()
+    }
+}
+
+/// removes lines starting with `comment_char` from the message git prepared,
+/// like `git commit --cleanup=strip` does for comments.
+///
+/// older git writes the `Conflicts:` block of merge and squash messages without comment char,
+/// so the block is also removed if `strip_conflicts` is true
+pub(crate) fn strip_comments(message: &str, comment_char: char, strip_conflicts: bool) -> String {
+    let mut result = String::with_capacity(message.len());
+    let mut in_conflicts = false;
+    for line in message.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if in_conflicts {
+            if line.starts_with('\t') {
+                continue;
+            }
+            in_conflicts = false;
+        }
+        if strip_conflicts && trimmed == "Conflicts:" {
+            in_conflicts = true;
+            continue;
+        }
+        if line.starts_with(comment_char) {
+            continue;
+        }
+        result.push_str(line);
+    }
+    result
+}
+
+/// guesses the comment char git chose for `core.commentChar=auto` from the prepared message.
+///
+/// git chooses the char from the message before adding the comments at the end,
+/// so the first candidate whose trailing comments leave a message git would choose it for
+/// is returned. without such comments, the message is considered to have no comments
+pub(crate) fn auto_comment_char(message: &str) -> char {
+    for c in COMMENT_CHAR_CANDIDATES.chars() {
+        let message = &message[..scissors_position(message, c).unwrap_or(message.len())];
+        let tail = comment_tail_position(message, c);
+        if tail < message.len() && select_comment_char(&message[..tail]) == c {
+            return c;
+        }
+    }
+    select_comment_char(message)
+}
+
+/// see adjust_comment_line_char in builtin/commit.c
+const COMMENT_CHAR_CANDIDATES: &str = "#;@!$%^&|:";
+
+/// chooses the comment char for the message like git: `#` if the message has no `#`,
+/// or the first candidate not starting any line. git fails if all candidates are used
+fn select_comment_char(message: &str) -> char {
+    if !message.contains('#') {
+        return '#';
+    }
+    let used = message
+        .split(['\n', '\r'])
+        .filter_map(|x| x.chars().next())
+        .collect::<Vec<_>>();
+    COMMENT_CHAR_CANDIDATES
+        .chars()
+        .find(|x| !used.contains(x))
+        .unwrap_or('#')
+}
+
+/// returns the byte offset of the comments and blank lines at the end of the message,
+/// like the ones git adds after the message. returns the length if there are no such comments
+pub(crate) fn comment_tail_position(message: &str, comment_char: char) -> usize {
+    let mut position = message.len();
+    let mut has_comment = false;
+    let mut offset = message.len();
+    for line in message.split_inclusive('\n').rev() {
+        offset -= line.len();
+        if line.starts_with(comment_char) {
+            has_comment = true;
+        } else if !line.trim().is_empty() {
+            break;
+        }
+        if has_comment {
+            position = offset;
+        }
+    }
+    position
+}
+
+#[test]
+fn comment_tail_position_test() {
+    let message = "feat: x\n\nbody\n\n# Please enter the commit message\n#\n";
+    assert_eq!(
+        &message[comment_tail_position(message, '#')..],
+        "\n# Please enter the commit message\n#\n"
+    );
+    assert_eq!(comment_tail_position(message, ';'), message.len());
+    // blank lines after the comments are also in the tail
+    let message = "feat: x\n; comment\n\n";
+    assert_eq!(
+        &message[comment_tail_position(message, ';')..],
+        "; comment\n\n"
+    );
+    // comments in the middle are not
+    let message = "feat: x\n\n# note\nbody\n";
+    assert_eq!(comment_tail_position(message, '#'), message.len());
+}
+
+/// returns the byte offset of the scissors line `git commit --verbose` puts before the diff.
+/// git ignores the scissors line and everything after it
+pub(crate) fn scissors_position(message: &str, comment_char: char) -> Option<usize> {
+    let mut offset = 0;
+    for line in message.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\r', '\n']);
+        if let Some(rest) = content.strip_prefix(comment_char) {
+            if rest == " ------------------------ >8 ------------------------" {
+                return Some(offset);
+            }
+        }
+        offset += line.len();
+    }
+    None
+}
+
+pub(crate) fn is_fence(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("```") || line.starts_with("~~~")
+}
+
+/// returns indices of blank lines following another blank line.
+/// blank lines in fenced code blocks and at the end of message are not included.
+pub(crate) fn extra_blank_lines(lines: &[&str]) -> Vec<usize> {
+    let end = lines
+        .iter()
+        .rposition(|x| !x.trim().is_empty())
+        .unwrap_or(0);
+    let mut in_fence = false;
+    let mut result = Vec::new();
+    for i in 1..end {
+        if is_fence(lines[i]) {
+            in_fence = !in_fence;
+        } else if !in_fence && lines[i].trim().is_empty() && lines[i - 1].trim().is_empty() {
+            result.push(i);
+        }
+    }
+    result
+}
+
+pub(crate) fn is_list_item(line: &str) -> bool {
+    let line = line.trim_start();
+    if line.starts_with("- ") || line.starts_with("* ") || line.starts_with("+ ") {
+        return true;
+    }
+    let digits = line.find(|x: char| !x.is_ascii_digit()).unwrap_or(0);
+    digits != 0 && (line[digits..].starts_with(". ") || line[digits..].starts_with(") "))
+}
+
+/// returns true if the value is in `Name <email@example.com>` format
+pub(crate) fn is_name_and_email(value: &str) -> bool {
+    let Some((name, email)) = value.trim_end().split_once('<') else {
+        return false;
+    };
+    let Some(email) = email.strip_suffix('>') else {
+        return false;
+    };
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+    !name.trim().is_empty()
+        && name.ends_with(' ')
+        && !local.is_empty()
+        && !domain.is_empty()
+        && !domain.contains('@')
+        && !email.contains(|x: char| x.is_whitespace() || x == '<' || x == '>')
+}
+
+/// finds the hash in `This reverts commit <hash>.` line git generates for revert commits
+pub(crate) fn reverted_commit<'a>(lines: &[&'a str]) -> Option<&'a str> {
+    lines.iter().find_map(|line| {
+        let rest = line.trim_start().strip_prefix("This reverts commit ")?;
+        let hash_end = rest
+            .find(|x: char| !x.is_ascii_hexdigit())
+            .unwrap_or(rest.len());
+        Some(&rest[..hash_end]).filter(|x| !x.is_empty())
+    })
+}
+
+/// finds issue references matching patterns other than `style`.
+/// matches overlapping with references in `style` are not reported
+pub(crate) fn foreign_issue_references<'a>(
+    text: &'a str,
+    patterns: &BTreeMap<String, Regex>,
+    style: &str,
+) -> Vec<&'a str> {
+    let allowed = patterns[style]
+        .find_iter(text)
+        .map(|x| x.range())
+        .collect::<Vec<_>>();
+    let mut found = patterns
+        .iter()
+        .filter(|(name, _)| *name != style)
+        .flat_map(|(_, pattern)| pattern.find_iter(text))
+        .filter(|x| {
+            !allowed
+                .iter()
+                .any(|a| a.start < x.end() && x.start() < a.end)
+        })
+        .map(|x| (x.start(), x.as_str()))
+        .collect::<Vec<_>>();
+    found.sort();
+    found.dedup();
+    found.into_iter().map(|(_, x)| x).collect()
+}
+
+/// returns true if the line has a closing keyword followed by issue reference like `fixes #1`
+pub(crate) fn has_closing_keyword(line: &str, keywords: &[String]) -> bool {
+    let words = line.split_whitespace().collect::<Vec<_>>();
+    words.windows(2).any(|pair| {
+        let keyword = pair[0].strip_suffix(':').unwrap_or(pair[0]);
+        let reference = pair[1];
+        let is_reference = reference.contains('#') || reference.contains("/issues/");
+        is_reference && keywords.iter().any(|x| x.eq_ignore_ascii_case(keyword))
+    })
+}
+
+fn is_continuation(line: &str) -> bool {
+    line.starts_with(char::is_whitespace) && !line.trim().is_empty()
+}
+
+/// parses `Token: value` or `Token #value` style trailer.
+///
+/// see <https://www.conventionalcommits.org/en/v1.0.0/#specification>
+pub(crate) fn parse_trailer(line: &str) -> Option<(&str, &str)> {
+    for breaking in ["BREAKING CHANGE", "BREAKING-CHANGE"] {
+        if let Some(value) = line
+            .strip_prefix(breaking)
+            .and_then(|x| x.strip_prefix(": "))
+        {
+            return Some((breaking, value));
+        }
+    }
+
+    let token_end = line.find(|x: char| !matches!(x, 'a'..='z' | 'A'..='Z' | '0'..='9' | '-'))?;
+    let (token, rest) = line.split_at(token_end);
+    if !token.starts_with(|x: char| x.is_ascii_alphabetic()) {
+        return None;
+    }
+    let value = rest
+        .strip_prefix(": ")
+        .or_else(|| rest.strip_prefix(" #"))?;
+    Some((token, value))
+}
+
+#[test]
+fn strip_comments_test() {
+    assert_eq!(
+        strip_comments(
+            "Merge branch 'feature'\n\
+            \n\
+            # Conflicts:\n\
+            #\tsrc/main.rs\n\
+            #\n\
+            # It looks like you may be committing a merge.\n\
+            # Please enter the commit message for your changes.\n",
+            '#',
+            false
+        ),
+        "Merge branch 'feature'\n\n"
+    );
+    assert_eq!(
+        strip_comments(
+            "fix: x\n\nbody\n\nConflicts:\n\tsrc/main.rs\n\tsrc/git.rs\n",
+            '#',
+            true
+        ),
+        "fix: x\n\nbody\n\n"
+    );
+    // the block may be written by the user
+    assert_eq!(
+        strip_comments("fix: x\n\nConflicts:\n\tnone\n", '#', false),
+        "fix: x\n\nConflicts:\n\tnone\n"
+    );
+    assert_eq!(
+        strip_comments("fix: x\n\nbody\n", '#', true),
+        "fix: x\n\nbody\n"
+    );
+    assert_eq!(
+        strip_comments("fix: x\n\n#1 is fixed\n; comment\n", ';', false),
+        "fix: x\n\n#1 is fixed\n"
+    );
+}
+
+#[test]
+fn scissors_position_test() {
+    let message = "feat: x\n\
+        \n\
+        body\n\
+        # Please enter the commit message for your changes. Lines starting\n\
+        # with '#' will be ignored, and an empty message aborts the commit.\n\
+        #\n\
+        # ------------------------ >8 ------------------------\n\
+        # Do not modify or remove the line above.\n\
+        # Everything below it will be ignored.\n\
+        diff --git a/a.txt b/a.txt\n\
+        index 7898192..6178079 100644\n\
+        --- a/a.txt\n\
+        +++ b/a.txt\n\
+        @@ -1 +1 @@\n\
+        -a\n\
+        +b\n";
+    let position = scissors_position(message, '#').unwrap();
+    assert!(message[..position].ends_with("#\n"));
+    assert!(message[position..].starts_with("# ------------------------ >8"));
+    assert_eq!(scissors_position(message, ';'), None);
+    assert_eq!(scissors_position("feat: x\n\nbody\n", '#'), None);
+}
+
+#[test]
+fn auto_comment_char_test() {
+    // the buffers `git commit -e` prepares with core.commentChar=auto
+    let comments = |c: char| {
+        format!(
+            "\n\
+            {c} Please enter the commit message for your changes. Lines starting\n\
+            {c} with '{c}' will be ignored, and an empty message aborts the commit.\n\
+            {c}\n\
+            {c} On branch main\n"
+        )
+    };
+    let test = |message: &str, c: char| {
+        assert_eq!(auto_comment_char(&format!("{message}{}", comments(c))), c);
+    };
+    test("feat: x\n\nbody\n", '#');
+    test("feat: x\n\n#1 is fixed\n", ';');
+    // no # in the message: lines starting with other candidates do not matter
+    test("feat: x\n\n;a\n\nbody\n", '#');
+    test("feat: x\n\n#1 is fixed\n;2\n@3\n", '!');
+    // only the first column is considered
+    test("feat: x\n\nissue #1\n;2\n", '#');
+    test("feat: x\n\n#1 is fixed\n  ;2\n", ';');
+
+    // the diff of `git commit --verbose` after the scissors line
+    let message = format!(
+        "feat: x\n\n#1 is fixed\n{}; ------------------------ >8 ------------------------\n\
+        diff --git a/a.txt b/a.txt\n",
+        comments(';')
+    );
+    assert_eq!(auto_comment_char(&message), ';');
+
+    // without comments like `git commit -m`
+    assert_eq!(auto_comment_char("feat: x\n"), '#');
+    assert_eq!(auto_comment_char("feat: x\n\nissue #1\n"), '#');
+}
+
+#[test]
+fn is_name_and_email_test() {
+    assert!(is_name_and_email("Name <name@example.com>"));
+    assert!(is_name_and_email("Full Name <name@example.com>"));
+    assert!(!is_name_and_email("Name"));
+    assert!(!is_name_and_email("name@example.com"));
+    assert!(!is_name_and_email("<name@example.com>"));
+    assert!(!is_name_and_email("Name<name@example.com>"));
+    assert!(!is_name_and_email("Name <name>"));
+    assert!(!is_name_and_email("Name <name@example.com"));
+    assert!(!is_name_and_email("Name <a@b@c>"));
+    assert!(!is_name_and_email("Name <na me@example.com>"));
+}
+
+#[test]
+fn extra_blank_lines_test() {
+    assert_eq!(
+        extra_blank_lines(&["feat: x", "", "a", "", "b"]),
+        Vec::<usize>::new()
+    );
+    assert_eq!(
+        extra_blank_lines(&["feat: x", "", "", "a", "", "", "", "b"]),
+        vec![2, 5, 6]
+    );
+    assert_eq!(
+        extra_blank_lines(&["feat: x", "", "```", "", "", "```", "b"]),
+        Vec::<usize>::new()
+    );
+    assert_eq!(
+        extra_blank_lines(&["feat: x", "", "a", "", ""]),
+        Vec::<usize>::new()
+    );
+}
+
+#[test]
+fn body_paragraphs_test() {
+    let message = Message::parse("feat: x\n\na\nb\n\n\nc\n\nRefs: #1\n");
+    assert_eq!(message.body_paragraphs(), vec![&["a", "b"][..], &["c"][..]]);
+    let message = Message::parse("feat: x\n\nRefs: #1\n");
+    assert!(message.body_paragraphs().is_empty());
+}
+
+#[test]
+fn reverted_commit_test() {
+    let hash = "e2f8b41fc7543b5e3b30de7543ec851a86ddd57c";
+    let message = format!("Revert \"feat: x\"\n\nThis reverts commit {hash}.\n");
+    let message = Message::parse(&message);
+    assert_eq!(reverted_commit(message.message_lines()), Some(hash));
+    assert_eq!(reverted_commit(&["This reverts commit ."]), None);
+    assert_eq!(reverted_commit(&["body"]), None);
+}
+
+#[test]
+fn multiline_footer_test() {
+    let message = Message::parse(
+        "feat!: x\n\
+        \n\
+        body\n\
+        \n\
+        BREAKING CHANGE: removed\n\
+        \x20 the old api\n\
+        Refs: #1\n",
+    );
+    assert_eq!(message.footer_start, 4);
+    assert!(!message.footer_follows_body());
+    assert_eq!(
+        message.footers,
+        vec![
+            Footer {
+                token: "BREAKING CHANGE",
+                value: "removed\nthe old api".to_string(),
+                line: 4,
+            },
+            Footer {
+                token: "Refs",
+                value: "#1".to_string(),
+                line: 6,
+            },
+        ]
+    );
+
+    // indented lines not following trailer are body
+    let message = Message::parse("feat: x\n\n  code\n");
+    assert!(message.footers.is_empty());
+    assert_eq!(message.body_paragraphs(), vec![&["  code"][..]]);
+}
+
+#[test]
+fn footer_without_blank_line_test() {
+    let message = Message::parse("feat: x\n\nbody\nRefs: #1\n  #2\nCloses #3\n");
+    assert_eq!(message.footer_start, 3);
+    assert!(message.footer_follows_body());
+    assert_eq!(message.body_paragraphs(), vec![&["body"][..]]);
+
+    // every line in the block must be a trailer or its continuation
+    let message = Message::parse("feat: x\n\nRefs: #1\nprose after it\n");
+    assert!(message.footers.is_empty());
+    assert!(!message.footer_follows_body());
+}
+
+#[test]
+fn parse_trailer_test() {
+    assert_eq!(parse_trailer("Refs: #123"), Some(("Refs", "#123")));
+    assert_eq!(parse_trailer("Closes #123"), Some(("Closes", "123")));
+    assert_eq!(
+        parse_trailer("Signed-off-by: Name <mail@example.com>"),
+        Some(("Signed-off-by", "Name <mail@example.com>"))
+    );
+    assert_eq!(
+        parse_trailer("BREAKING CHANGE: removed api"),
+        Some(("BREAKING CHANGE", "removed api"))
+    );
+    assert_eq!(parse_trailer("Some prose: with colon"), None);
+    assert_eq!(parse_trailer("Token:no space"), None);
+    assert_eq!(parse_trailer("-token: value"), None);
+}
+
+#[test]
+fn has_closing_keyword_test() {
+    let keywords = ["Closes", "Fixes"].map(String::from);
+    assert!(has_closing_keyword("this closes #1", &keywords));
+    assert!(has_closing_keyword("Fixes: owner/repo#1", &keywords));
+    assert!(has_closing_keyword(
+        "fixes https://github.com/o/r/issues/1",
+        &keywords
+    ));
+    assert!(!has_closing_keyword("this fixes the crash", &keywords));
+    assert!(!has_closing_keyword("resolves #1", &keywords));
+    assert!(!has_closing_keyword("prefixes #1", &keywords));
+}
+
+#[test]
+fn foreign_issue_references_test() {
+    let patterns = crate::config::Config::default().issue_reference_patterns;
+    let text = "fixes #1 and GH-2\nsee https://github.com/o/r/issues/3";
+    assert_eq!(
+        foreign_issue_references(text, &patterns, "hash"),
+        vec!["GH-2", "https://github.com/o/r/issues/3"]
+    );
+    assert_eq!(
+        foreign_issue_references(text, &patterns, "gh"),
+        vec!["#1", "https://github.com/o/r/issues/3"]
+    );
+    assert_eq!(
+        foreign_issue_references("see https://github.com/o/r/issues/3#4", &patterns, "url"),
+        Vec::<&str>::new()
+    );
+    assert_eq!(
+        foreign_issue_references("fixes #1, #2 (#3)", &patterns, "hash"),
+        Vec::<&str>::new()
+    );
+}
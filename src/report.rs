@@ -0,0 +1,458 @@
+use crate::config::Config;
+use crate::{has_error, CommitReport, MessageError, Severity};
+use std::fmt::Write;
+
+/// the output format of check mode
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum OutputFormat {
+    /// human readable messages to stderr
+    Text,
+    /// JUnit XML to stdout, one testcase for each commit
+    Junit,
+    /// SARIF 2.1.0 to stdout, one result for each error
+    Sarif,
+    /// JSON array to stdout, one object for each error
+    Json,
+    /// GitHub Actions workflow commands to stdout, one annotation for each error
+    Github,
+}
+
+impl OutputFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(Self::Text),
+            "junit" => Some(Self::Junit),
+            "sarif" => Some(Self::Sarif),
+            "json" => Some(Self::Json),
+            "github" => Some(Self::Github),
+            "auto" => Some(Self::auto()),
+            _ => None,
+        }
+    }
+
+    /// the format for `auto`: `Github` on GitHub Actions, `Text` otherwise
+    pub fn auto() -> Self {
+        if std::env::var("GITHUB_ACTIONS").is_ok_and(|x| x == "true") {
+            Self::Github
+        } else {
+            Self::Text
+        }
+    }
+}
+
+/// formats the results as human readable messages
+pub(crate) fn text(
+    results: &[(Option<String>, Vec<CommitReport>)],
+    config: &Config,
+    show_subject: bool,
+) -> String {
+    let mut text = String::new();
+    for (label, reports) in results {
+        if let Some(label) = label {
+            let failed = reports
+                .iter()
+                .filter(|x| has_error(&x.errors, config))
+                .count();
+            writeln!(text, "{label}: {} commits, {failed} failing", reports.len()).unwrap();
+        }
+        for report in reports {
+            if !report.errors.is_empty() {
+                writeln!(text, "{}", config.messages.banner(report.hash)).unwrap();
+                if show_subject {
+                    let subject = String::from_utf8_lossy(report.commit.subject());
+                    writeln!(text, "  subject: {}", subject).unwrap();
+                }
+                text.push_str(&errors_text(&report.errors, config));
+            }
+        }
+    }
+    text
+}
+
+/// formats the errors as indented lines prefixed with the severity
+pub(crate) fn errors_text(errors: &[MessageError], config: &Config) -> String {
+    let mut text = String::new();
+    for x in errors {
+        let severity = severity_name(x.severity(config));
+        writeln!(text, "  {severity}: {}", x.message(config)).unwrap();
+    }
+    text
+}
+
+/// formats the results as JUnit XML with one testsuite for each range
+pub(crate) fn junit(results: &[(Option<String>, Vec<CommitReport>)], config: &Config) -> String {
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<testsuites>\n");
+    for (label, reports) in results {
+        let name = label.as_deref().unwrap_or("conventional-commitlint");
+        let failures = reports
+            .iter()
+            .filter(|x| has_error(&x.errors, config))
+            .count();
+        writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{failures}\">",
+            escape_xml(name),
+            reports.len(),
+        )
+        .unwrap();
+        for report in reports {
+            let subject = String::from_utf8_lossy(report.commit.subject());
+            write!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"{}\"",
+                report.hash,
+                escape_xml(&subject),
+            )
+            .unwrap();
+            if report.errors.is_empty() {
+                xml.push_str("/>\n");
+                continue;
+            }
+            xml.push_str(">\n");
+            let messages = |severity: Severity| {
+                report
+                    .errors
+                    .iter()
+                    .filter(|x| x.severity(config) == severity)
+                    .map(|x| x.message(config))
+                    .collect::<Vec<_>>()
+            };
+            let errors = messages(Severity::Error);
+            if !errors.is_empty() {
+                writeln!(
+                    xml,
+                    "      <failure message=\"{}\">{}</failure>",
+                    escape_xml(&config.messages.banner(report.hash)),
+                    escape_xml(&errors.join("\n")),
+                )
+                .unwrap();
+            }
+            let warnings = messages(Severity::Warning);
+            if !warnings.is_empty() {
+                let warnings = warnings.iter().map(|x| format!("warning: {x}"));
+                let warnings = warnings.collect::<Vec<_>>().join("\n");
+                writeln!(
+                    xml,
+                    "      <system-out>{}</system-out>",
+                    escape_xml(&warnings)
+                )
+                .unwrap();
+            }
+            xml.push_str("    </testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+/// formats the errors of all commits as JSON array
+pub(crate) fn json(results: &[(Option<String>, Vec<CommitReport>)], config: &Config) -> String {
+    let mut errors = Vec::new();
+    for report in results.iter().flat_map(|(_, x)| x) {
+        for error in &report.errors {
+            let mut error = error_json(error, config);
+            error["commit"] = report.hash.to_string().into();
+            errors.push(error);
+        }
+    }
+    let mut json = serde_json::to_string_pretty(&errors).unwrap();
+    json.push('\n');
+    json
+}
+
+/// formats the errors of a message as JSON array
+pub(crate) fn errors_json(errors: &[MessageError], config: &Config) -> String {
+    let errors = errors
+        .iter()
+        .map(|x| error_json(x, config))
+        .collect::<Vec<_>>();
+    let mut json = serde_json::to_string_pretty(&errors).unwrap();
+    json.push('\n');
+    json
+}
+
+fn error_json(error: &MessageError, config: &Config) -> serde_json::Value {
+    serde_json::json!({
+        "code": error.code(),
+        "severity": severity_name(error.severity(config)),
+        "message": error.message(config),
+    })
+}
+
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    }
+}
+
+/// formats the results as GitHub Actions workflow commands to annotate errors
+pub(crate) fn github(results: &[(Option<String>, Vec<CommitReport>)], config: &Config) -> String {
+    let mut commands = String::new();
+    for report in results.iter().flat_map(|(_, x)| x) {
+        for error in &report.errors {
+            let message = format!("commit {}: {}", report.hash, error.message(config));
+            github_command(&mut commands, error.severity(config), &message);
+        }
+    }
+    commands
+}
+
+/// formats the errors of a message as GitHub Actions workflow commands
+pub(crate) fn errors_github(errors: &[MessageError], config: &Config) -> String {
+    let mut commands = String::new();
+    for error in errors {
+        github_command(
+            &mut commands,
+            error.severity(config),
+            &error.message(config),
+        );
+    }
+    commands
+}
+
+fn github_command(commands: &mut String, severity: Severity, message: &str) {
+    // see https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions
+    let message = message
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A");
+    writeln!(commands, "::{}::{message}", severity_name(severity)).unwrap();
+}
+
+/// the artifact of SARIF results. consumers like GitHub code scanning expect a file path
+/// so the file git uses for commit messages is used instead of commit hashes
+const COMMIT_MESSAGE_URI: &str = ".git/COMMIT_EDITMSG";
+
+/// formats the results as SARIF 2.1.0 log.
+/// since errors are not in files, commits are reported as logical locations
+pub(crate) fn sarif(results: &[(Option<String>, Vec<CommitReport>)], config: &Config) -> String {
+    let mut sarif_results = Vec::new();
+    for report in results.iter().flat_map(|(_, x)| x) {
+        let hash = report.hash.to_string();
+        let subject = String::from_utf8_lossy(report.commit.subject());
+        for error in &report.errors {
+            sarif_results.push(serde_json::json!({
+                "ruleId": error.code(),
+                "level": severity_name(error.severity(config)),
+                "message": { "text": format!("{} in commit {hash}", error.message(config)) },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": COMMIT_MESSAGE_URI },
+                    },
+                    "logicalLocations": [{ "name": hash, "kind": "resource" }],
+                }],
+                "properties": { "commit": hash, "subject": subject },
+            }));
+        }
+    }
+
+    let rules = MessageError::CODES
+        .iter()
+        .map(|code| serde_json::json!({ "id": code }))
+        .collect::<Vec<_>>();
+    let log = serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": sarif_results,
+        }],
+    });
+    let mut sarif = serde_json::to_string_pretty(&log).unwrap();
+    sarif.push('\n');
+    sarif
+}
+
+/// escapes text for both of XML attributes and contents.
+/// control characters not allowed in XML 1.0 are replaced with U+FFFD
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            '\0'..='\u{1f}' => escaped.push('\u{FFFD}'),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[test]
+fn escape_xml_test() {
+    assert_eq!(escape_xml("feat: x"), "feat: x");
+    assert_eq!(
+        escape_xml("fix: <a> & \"b\" 'c'"),
+        "fix: &lt;a&gt; &amp; &quot;b&quot; &apos;c&apos;"
+    );
+    assert_eq!(escape_xml("a\u{1b}b\nc"), "a\u{FFFD}b\nc");
+}
+
+#[test]
+fn junit_test() {
+    use crate::git::{CommitObject, ObjectHash};
+
+    let report = |subject: &str, errors| CommitReport {
+        hash: ObjectHash::from_hex("e2f8b41fc7543b5e3b30de7543ec851a86ddd57c").unwrap(),
+        commit: CommitObject::parse(format!("tree x\n\n{subject}\n").as_bytes()).unwrap(),
+        errors,
+    };
+    let results = vec![(
+        None,
+        vec![
+            report("feat: ok", vec![]),
+            report("Feat: <x>", vec![MessageError::HeaderTypeNotLower]),
+        ],
+    )];
+    let xml = junit(&results, &Config::default());
+    assert!(xml.contains("<testsuite name=\"conventional-commitlint\" tests=\"2\" failures=\"1\">"));
+    assert!(xml.contains("classname=\"feat: ok\"/>"));
+    assert!(xml.contains("classname=\"Feat: &lt;x&gt;\">"));
+    assert!(xml.contains(">commit type is not lowercase</failure>"));
+}
+
+#[test]
+fn sarif_test() {
+    use crate::git::{CommitObject, ObjectHash};
+
+    let hash = "e2f8b41fc7543b5e3b30de7543ec851a86ddd57c";
+    let results = vec![(
+        None,
+        vec![CommitReport {
+            hash: ObjectHash::from_hex(hash).unwrap(),
+            commit: CommitObject::from_message(b"Feat: \"x\"\n".to_vec()),
+            errors: vec![
+                MessageError::HeaderTypeNotLower,
+                MessageError::RepeatedSubject("Feat: \"x\"".to_string()),
+            ],
+        }],
+    )];
+    let sarif = sarif(&results, &Config::default());
+    let log = serde_json::from_str::<serde_json::Value>(&sarif).unwrap();
+    assert_eq!(log["version"], "2.1.0");
+    let sarif_results = log["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(sarif_results.len(), 2);
+    assert_eq!(sarif_results[0]["ruleId"], "HeaderTypeNotLower");
+    assert_eq!(sarif_results[0]["level"], "error");
+    assert_eq!(sarif_results[1]["level"], "warning");
+    assert_eq!(sarif_results[0]["properties"]["commit"], hash);
+    assert_eq!(sarif_results[0]["properties"]["subject"], "Feat: \"x\"");
+    assert_eq!(
+        sarif_results[0]["locations"][0]["logicalLocations"][0]["name"],
+        hash
+    );
+    assert_eq!(
+        sarif_results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        ".git/COMMIT_EDITMSG"
+    );
+    let rules = log["runs"][0]["tool"]["driver"]["rules"]
+        .as_array()
+        .unwrap();
+    assert_eq!(rules.len(), MessageError::CODES.len());
+    assert!(rules.iter().any(|x| x["id"] == "HeaderTypeNotLower"));
+}
+
+#[test]
+fn json_test() {
+    use crate::git::{CommitObject, ObjectHash};
+
+    let hash = "e2f8b41fc7543b5e3b30de7543ec851a86ddd57c";
+    let results = vec![(
+        None,
+        vec![CommitReport {
+            hash: ObjectHash::from_hex(hash).unwrap(),
+            commit: CommitObject::from_message(b"fix: ".to_vec()),
+            errors: vec![MessageError::HeaderSubjectEmpty],
+        }],
+    )];
+    let json = json(&results, &Config::default());
+    let errors = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+    assert_eq!(
+        errors,
+        serde_json::json!([{
+            "commit": hash,
+            "code": "HeaderSubjectEmpty",
+            "severity": "error",
+            "message": "commit subject is empty",
+        }])
+    );
+    assert_eq!(errors_json(&[], &Config::default()), "[]\n");
+}
+
+#[test]
+fn github_test() {
+    use crate::git::{CommitObject, ObjectHash};
+
+    let hash = "e2f8b41fc7543b5e3b30de7543ec851a86ddd57c";
+    let results = vec![(
+        None,
+        vec![CommitReport {
+            hash: ObjectHash::from_hex(hash).unwrap(),
+            commit: CommitObject::from_message(b"Feat: x".to_vec()),
+            errors: vec![
+                MessageError::HeaderTypeNotLower,
+                MessageError::RepeatedSubject("100%\nx".to_string()),
+            ],
+        }],
+    )];
+    assert_eq!(
+        github(&results, &Config::default()),
+        format!(
+            "::error::commit {hash}: commit type is not lowercase\n\
+            ::warning::commit {hash}: same subject is repeated consecutively: 100%25%0Ax\n"
+        )
+    );
+    assert_eq!(
+        errors_github(&[MessageError::HeaderTypeNotLower], &Config::default()),
+        "::error::commit type is not lowercase\n"
+    );
+}
+
+#[test]
+fn errors_text_test() {
+    let errors = [
+        MessageError::HeaderTypeNotLower,
+        MessageError::InsufficientDescription,
+    ];
+    assert_eq!(
+        errors_text(&errors, &Config::default()),
+        "  error: commit type is not lowercase\n  warning: commit message seems too short to describe the change\n"
+    );
+
+    let config = Config {
+        severity: [
+            ("HeaderTypeNotLower".to_string(), Severity::Warning),
+            ("InsufficientDescription".to_string(), Severity::Error),
+        ]
+        .into(),
+        ..Config::default()
+    };
+    assert_eq!(
+        errors_text(&errors, &config),
+        "  warning: commit type is not lowercase\n  error: commit message seems too short to describe the change\n"
+    );
+    assert!(has_error(&errors[1..], &config));
+    assert!(!has_error(&errors[..1], &config));
+}
+
+#[test]
+fn output_format_parse_test() {
+    assert_eq!(OutputFormat::parse("text"), Some(OutputFormat::Text));
+    assert_eq!(OutputFormat::parse("sarif"), Some(OutputFormat::Sarif));
+    assert_eq!(OutputFormat::parse("auto"), Some(OutputFormat::auto()));
+    assert_eq!(OutputFormat::parse("xml"), None);
+}